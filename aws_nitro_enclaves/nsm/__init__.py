@@ -1,13 +1,18 @@
 """Public interface for interacting with the Nitro Secure Module (NSM)."""
 
+import re
 from importlib import metadata
 
-from .client import NsmClient
+from ._sample import sample_attestation
+from ._transport import derive_module_id, is_canonical_nsm_path, is_real_enclave
+from .client import NsmClient, NsmReadOnlyView, sessions_state_equal, verify_attestation
 from .errors import (
     NsmDeviceNotFoundError,
     NsmError,
     NsmPcrLockedError,
     NsmSessionClosedError,
+    NsmSessionFrozenError,
+    error_codes,
 )
 
 try:
@@ -22,11 +27,35 @@ def sdk_version() -> str:
     return NsmClient.sdk_version()
 
 
+def version_at_least(major: int, minor: int, patch: int) -> bool:
+    """Return whether the SDK version is at least ``major.minor.patch``.
+
+    Pre-release and local suffixes (``1.2.0rc1``, ``1.2.0+dev``) are ignored, so
+    ``1.2.0rc1`` satisfies ``version_at_least(1, 2, 0)``.
+    """
+
+    match = re.match(r"(\d+)(?:\.(\d+))?(?:\.(\d+))?", sdk_version())
+    if match is None:
+        return False
+    current = tuple(int(part or 0) for part in match.groups())
+    return current >= (major, minor, patch)
+
+
 __all__ = [
     "NsmClient",
+    "NsmReadOnlyView",
     "NsmError",
     "NsmDeviceNotFoundError",
     "NsmSessionClosedError",
+    "NsmSessionFrozenError",
     "NsmPcrLockedError",
+    "derive_module_id",
+    "error_codes",
+    "is_canonical_nsm_path",
+    "is_real_enclave",
+    "sample_attestation",
     "sdk_version",
+    "sessions_state_equal",
+    "verify_attestation",
+    "version_at_least",
 ]
@@ -0,0 +1,58 @@
+"""Minimal deterministic CBOR encoder (RFC 8949, section 4.2.1).
+
+Only the types produced by the SDK itself are supported: ``None``, ``bool``,
+``int``, ``bytes``, ``str``, sequences and mappings. Map keys are sorted by the
+bytewise order of their encodings so identical values always encode identically.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Mapping, Sequence
+
+_UNSIGNED = 0
+_NEGATIVE = 1
+_BYTES = 2
+_TEXT = 3
+_ARRAY = 4
+_MAP = 5
+
+
+def _head(major: int, value: int) -> bytes:
+    if value < 24:
+        return bytes([(major << 5) | value])
+    if value < 0x100:
+        return bytes([(major << 5) | 24]) + value.to_bytes(1, "big")
+    if value < 0x10000:
+        return bytes([(major << 5) | 25]) + value.to_bytes(2, "big")
+    if value < 0x100000000:
+        return bytes([(major << 5) | 26]) + value.to_bytes(4, "big")
+    if value < 0x10000000000000000:
+        return bytes([(major << 5) | 27]) + value.to_bytes(8, "big")
+    raise ValueError("integer is too large for CBOR encoding")
+
+
+def dumps(value: Any) -> bytes:
+    """Encode ``value`` as deterministic CBOR."""
+
+    if value is None:
+        return b"\xf6"
+    if value is True:
+        return b"\xf5"
+    if value is False:
+        return b"\xf4"
+    if isinstance(value, int):
+        if value >= 0:
+            return _head(_UNSIGNED, value)
+        return _head(_NEGATIVE, -1 - value)
+    if isinstance(value, (bytes, bytearray, memoryview)):
+        data = bytes(value)
+        return _head(_BYTES, len(data)) + data
+    if isinstance(value, str):
+        text = value.encode("utf-8")
+        return _head(_TEXT, len(text)) + text
+    if isinstance(value, Mapping):
+        items = sorted((dumps(key), dumps(item)) for key, item in value.items())
+        return _head(_MAP, len(items)) + b"".join(key + item for key, item in items)
+    if isinstance(value, Sequence):
+        return _head(_ARRAY, len(value)) + b"".join(dumps(item) for item in value)
+    raise TypeError(f"cannot CBOR-encode value of type {type(value).__name__}")
@@ -22,7 +22,9 @@ ffibuilder.cdef(
 
     int nsm_get_random(nsm_session *session, unsigned char *out, size_t length);
     int nsm_describe_pcr(const nsm_session *session, uint32_t slot, unsigned char *out);
+    int nsm_extend_digest(const unsigned char *current, const unsigned char *data, size_t length, unsigned char *out);
     int nsm_extend_pcr(nsm_session *session, uint32_t slot, const unsigned char *data, size_t length, unsigned char *out);
+    int nsm_set_pcr(nsm_session *session, uint32_t slot, const unsigned char *digest);
     int nsm_lock_pcr(nsm_session *session, uint32_t slot);
     int nsm_lock_range(nsm_session *session, uint32_t limit);
 
@@ -81,7 +83,9 @@ ffibuilder.set_source(
 
     int nsm_get_random(nsm_session *session, unsigned char *out, size_t length);
     int nsm_describe_pcr(const nsm_session *session, uint32_t slot, unsigned char *out);
+    int nsm_extend_digest(const unsigned char *current, const unsigned char *data, size_t length, unsigned char *out);
     int nsm_extend_pcr(nsm_session *session, uint32_t slot, const unsigned char *data, size_t length, unsigned char *out);
+    int nsm_set_pcr(nsm_session *session, uint32_t slot, const unsigned char *digest);
     int nsm_lock_pcr(nsm_session *session, uint32_t slot);
     int nsm_lock_range(nsm_session *session, uint32_t limit);
 
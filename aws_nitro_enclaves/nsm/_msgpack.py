@@ -0,0 +1,62 @@
+"""Minimal MessagePack encoder.
+
+Only the types produced by the SDK itself are supported: ``None``, ``bool``,
+``int``, ``bytes``, ``str``, sequences and mappings. Byte strings are emitted
+with the ``bin`` family so they round-trip as binary rather than text. Mapping
+order is preserved.
+"""
+
+from __future__ import annotations
+
+import struct
+from typing import Any, Mapping, Sequence
+
+
+def _sized(fix_base: int, fix_limit: int, codes: Sequence[int], size: int) -> bytes:
+    if fix_limit and size < fix_limit:
+        return bytes([fix_base | size])
+    for code, width in zip(codes, (1, 2, 4)):
+        if code and size < (1 << (8 * width)):
+            return bytes([code]) + size.to_bytes(width, "big")
+    raise ValueError("value is too large for MessagePack encoding")
+
+
+_UNSIGNED = ((0xCC, ">B"), (0xCD, ">H"), (0xCE, ">I"), (0xCF, ">Q"))
+_SIGNED = ((0xD0, ">b"), (0xD1, ">h"), (0xD2, ">i"), (0xD3, ">q"))
+
+
+def _int(value: int) -> bytes:
+    if -32 <= value < 0x80:
+        return struct.pack(">b" if value < 0 else ">B", value)
+    for code, fmt in _UNSIGNED if value >= 0 else _SIGNED:
+        try:
+            return bytes([code]) + struct.pack(fmt, value)
+        except struct.error:
+            continue
+    raise ValueError("integer is too large for MessagePack encoding")
+
+
+def dumps(value: Any) -> bytes:
+    """Encode ``value`` as MessagePack."""
+
+    if value is None:
+        return b"\xc0"
+    if value is True:
+        return b"\xc3"
+    if value is False:
+        return b"\xc2"
+    if isinstance(value, int):
+        return _int(value)
+    if isinstance(value, (bytes, bytearray, memoryview)):
+        data = bytes(value)
+        return _sized(0, 0, (0xC4, 0xC5, 0xC6), len(data)) + data
+    if isinstance(value, str):
+        text = value.encode("utf-8")
+        return _sized(0xA0, 32, (0xD9, 0xDA, 0xDB), len(text)) + text
+    if isinstance(value, Mapping):
+        head = _sized(0x80, 16, (0, 0xDE, 0xDF), len(value))
+        return head + b"".join(dumps(key) + dumps(item) for key, item in value.items())
+    if isinstance(value, Sequence):
+        head = _sized(0x90, 16, (0, 0xDC, 0xDD), len(value))
+        return head + b"".join(dumps(item) for item in value)
+    raise TypeError(f"cannot MessagePack-encode value of type {type(value).__name__}")
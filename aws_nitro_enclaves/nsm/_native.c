@@ -606,7 +606,9 @@ static void (*_cffi_call_python_org)(struct _cffi_externpy_s *, char *);
 
     int nsm_get_random(nsm_session *session, unsigned char *out, size_t length);
     int nsm_describe_pcr(const nsm_session *session, uint32_t slot, unsigned char *out);
+    int nsm_extend_digest(const unsigned char *current, const unsigned char *data, size_t length, unsigned char *out);
     int nsm_extend_pcr(nsm_session *session, uint32_t slot, const unsigned char *data, size_t length, unsigned char *out);
+    int nsm_set_pcr(nsm_session *session, uint32_t slot, const unsigned char *digest);
     int nsm_lock_pcr(nsm_session *session, uint32_t slot);
     int nsm_lock_range(nsm_session *session, uint32_t limit);
 
@@ -621,78 +623,89 @@ static void (*_cffi_call_python_org)(struct _cffi_externpy_s *, char *);
 /************************************************************/
 
 static void *_cffi_types[] = {
-/*  0 */ _CFFI_OP(_CFFI_OP_FUNCTION, 56), // char const *()(nsm_session const *)
-/*  1 */ _CFFI_OP(_CFFI_OP_POINTER, 61), // nsm_session const *
+/*  0 */ _CFFI_OP(_CFFI_OP_FUNCTION, 67), // char const *()(nsm_session const *)
+/*  1 */ _CFFI_OP(_CFFI_OP_POINTER, 72), // nsm_session const *
 /*  2 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/*  3 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session *)
-/*  4 */ _CFFI_OP(_CFFI_OP_POINTER, 61), // nsm_session *
+/*  3 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *)
+/*  4 */ _CFFI_OP(_CFFI_OP_POINTER, 72), // nsm_session *
 /*  5 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/*  6 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session *, uint32_t)
+/*  6 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *, uint32_t)
 /*  7 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
 /*  8 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22), // uint32_t
 /*  9 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 10 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session *, uint32_t, unsigned char const *, size_t)
+/* 10 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *, uint32_t, unsigned char const *)
 /* 11 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
 /* 12 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
-/* 13 */ _CFFI_OP(_CFFI_OP_POINTER, 66), // unsigned char const *
-/* 14 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28), // size_t
-/* 15 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 16 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session *, uint32_t, unsigned char const *, size_t, unsigned char *)
-/* 17 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
-/* 18 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
-/* 19 */ _CFFI_OP(_CFFI_OP_NOOP, 13),
-/* 20 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
-/* 21 */ _CFFI_OP(_CFFI_OP_POINTER, 66), // unsigned char *
-/* 22 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 23 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session *, unsigned char *, size_t)
-/* 24 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
-/* 25 */ _CFFI_OP(_CFFI_OP_NOOP, 21),
-/* 26 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
+/* 13 */ _CFFI_OP(_CFFI_OP_POINTER, 77), // unsigned char const *
+/* 14 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 15 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *, uint32_t, unsigned char const *, size_t)
+/* 16 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
+/* 17 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
+/* 18 */ _CFFI_OP(_CFFI_OP_NOOP, 13),
+/* 19 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28), // size_t
+/* 20 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 21 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *, uint32_t, unsigned char const *, size_t, unsigned char *)
+/* 22 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
+/* 23 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
+/* 24 */ _CFFI_OP(_CFFI_OP_NOOP, 13),
+/* 25 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
+/* 26 */ _CFFI_OP(_CFFI_OP_POINTER, 77), // unsigned char *
 /* 27 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 28 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session const *)
-/* 29 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
-/* 30 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 31 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session const *, uint32_t, unsigned char *)
-/* 32 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
-/* 33 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
-/* 34 */ _CFFI_OP(_CFFI_OP_NOOP, 21),
+/* 28 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session *, unsigned char *, size_t)
+/* 29 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
+/* 30 */ _CFFI_OP(_CFFI_OP_NOOP, 26),
+/* 31 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
+/* 32 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 33 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session const *)
+/* 34 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
 /* 35 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 36 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session const *, uint32_t, unsigned char const * *, size_t *)
+/* 36 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session const *, uint32_t, unsigned char *)
 /* 37 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
 /* 38 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
-/* 39 */ _CFFI_OP(_CFFI_OP_POINTER, 13), // unsigned char const * *
-/* 40 */ _CFFI_OP(_CFFI_OP_POINTER, 14), // size_t *
-/* 41 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 42 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session const *, unsigned char *)
-/* 43 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
-/* 44 */ _CFFI_OP(_CFFI_OP_NOOP, 21),
-/* 45 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 46 */ _CFFI_OP(_CFFI_OP_FUNCTION, 60), // int()(nsm_session const *, unsigned char *, size_t)
-/* 47 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
-/* 48 */ _CFFI_OP(_CFFI_OP_NOOP, 21),
-/* 49 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
+/* 39 */ _CFFI_OP(_CFFI_OP_NOOP, 26),
+/* 40 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 41 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session const *, uint32_t, unsigned char const * *, size_t *)
+/* 42 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
+/* 43 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 22),
+/* 44 */ _CFFI_OP(_CFFI_OP_POINTER, 13), // unsigned char const * *
+/* 45 */ _CFFI_OP(_CFFI_OP_POINTER, 19), // size_t *
+/* 46 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 47 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session const *, unsigned char *)
+/* 48 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
+/* 49 */ _CFFI_OP(_CFFI_OP_NOOP, 26),
 /* 50 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 51 */ _CFFI_OP(_CFFI_OP_FUNCTION, 4), // nsm_session *()(void)
-/* 52 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 53 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // void()(nsm_session *)
-/* 54 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
+/* 51 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(nsm_session const *, unsigned char *, size_t)
+/* 52 */ _CFFI_OP(_CFFI_OP_NOOP, 1),
+/* 53 */ _CFFI_OP(_CFFI_OP_NOOP, 26),
+/* 54 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
 /* 55 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
-/* 56 */ _CFFI_OP(_CFFI_OP_POINTER, 57), // char const *
-/* 57 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 2), // char
-/* 58 */ _CFFI_OP(_CFFI_OP_ARRAY, 57), // char[33]
-/* 59 */ (_cffi_opcode_t)(33),
-/* 60 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 7), // int
-/* 61 */ _CFFI_OP(_CFFI_OP_STRUCT_UNION, 0), // nsm_session
-/* 62 */ _CFFI_OP(_CFFI_OP_ARRAY, 14), // size_t[4]
-/* 63 */ (_cffi_opcode_t)(4),
-/* 64 */ _CFFI_OP(_CFFI_OP_ARRAY, 21), // unsigned char *[4]
-/* 65 */ (_cffi_opcode_t)(4),
-/* 66 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 4), // unsigned char
-/* 67 */ _CFFI_OP(_CFFI_OP_ARRAY, 66), // unsigned char[32]
-/* 68 */ (_cffi_opcode_t)(32),
-/* 69 */ _CFFI_OP(_CFFI_OP_ARRAY, 67), // unsigned char[32][32]
-/* 70 */ (_cffi_opcode_t)(32),
-/* 71 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 0), // void
+/* 56 */ _CFFI_OP(_CFFI_OP_FUNCTION, 71), // int()(unsigned char const *, unsigned char const *, size_t, unsigned char *)
+/* 57 */ _CFFI_OP(_CFFI_OP_NOOP, 13),
+/* 58 */ _CFFI_OP(_CFFI_OP_NOOP, 13),
+/* 59 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 28),
+/* 60 */ _CFFI_OP(_CFFI_OP_NOOP, 26),
+/* 61 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 62 */ _CFFI_OP(_CFFI_OP_FUNCTION, 4), // nsm_session *()(void)
+/* 63 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 64 */ _CFFI_OP(_CFFI_OP_FUNCTION, 82), // void()(nsm_session *)
+/* 65 */ _CFFI_OP(_CFFI_OP_NOOP, 4),
+/* 66 */ _CFFI_OP(_CFFI_OP_FUNCTION_END, 0),
+/* 67 */ _CFFI_OP(_CFFI_OP_POINTER, 68), // char const *
+/* 68 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 2), // char
+/* 69 */ _CFFI_OP(_CFFI_OP_ARRAY, 68), // char[33]
+/* 70 */ (_cffi_opcode_t)(33),
+/* 71 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 7), // int
+/* 72 */ _CFFI_OP(_CFFI_OP_STRUCT_UNION, 0), // nsm_session
+/* 73 */ _CFFI_OP(_CFFI_OP_ARRAY, 19), // size_t[4]
+/* 74 */ (_cffi_opcode_t)(4),
+/* 75 */ _CFFI_OP(_CFFI_OP_ARRAY, 26), // unsigned char *[4]
+/* 76 */ (_cffi_opcode_t)(4),
+/* 77 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 4), // unsigned char
+/* 78 */ _CFFI_OP(_CFFI_OP_ARRAY, 77), // unsigned char[32]
+/* 79 */ (_cffi_opcode_t)(32),
+/* 80 */ _CFFI_OP(_CFFI_OP_ARRAY, 78), // unsigned char[32][32]
+/* 81 */ (_cffi_opcode_t)(32),
+/* 82 */ _CFFI_OP(_CFFI_OP_PRIMITIVE, 0), // void
 };
 
 _CFFI_UNUSED_FN
@@ -739,10 +752,10 @@ _cffi_f_nsm_attestation_digest(PyObject *self, PyObject *args)
   }
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(21), arg1, (char **)&x1);
+      _cffi_type(26), arg1, (char **)&x1);
   if (datasize != 0) {
     x1 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(21), arg1, (char **)&x1,
+    if (_cffi_convert_array_argument(_cffi_type(26), arg1, (char **)&x1,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -800,19 +813,19 @@ _cffi_f_nsm_describe_certificate(PyObject *self, PyObject *args)
     return NULL;
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(39), arg2, (char **)&x2);
+      _cffi_type(44), arg2, (char **)&x2);
   if (datasize != 0) {
     x2 = ((size_t)datasize) <= 640 ? (unsigned char const * *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(39), arg2, (char **)&x2,
+    if (_cffi_convert_array_argument(_cffi_type(44), arg2, (char **)&x2,
             datasize, &large_args_free) < 0)
       return NULL;
   }
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(40), arg3, (char **)&x3);
+      _cffi_type(45), arg3, (char **)&x3);
   if (datasize != 0) {
     x3 = ((size_t)datasize) <= 640 ? (size_t *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(40), arg3, (char **)&x3,
+    if (_cffi_convert_array_argument(_cffi_type(45), arg3, (char **)&x3,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -868,10 +881,10 @@ _cffi_f_nsm_describe_pcr(PyObject *self, PyObject *args)
     return NULL;
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(21), arg2, (char **)&x2);
+      _cffi_type(26), arg2, (char **)&x2);
   if (datasize != 0) {
     x2 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(21), arg2, (char **)&x2,
+    if (_cffi_convert_array_argument(_cffi_type(26), arg2, (char **)&x2,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -891,6 +904,76 @@ _cffi_f_nsm_describe_pcr(PyObject *self, PyObject *args)
 #  define _cffi_f_nsm_describe_pcr _cffi_d_nsm_describe_pcr
 #endif
 
+static int _cffi_d_nsm_extend_digest(unsigned char const * x0, unsigned char const * x1, size_t x2, unsigned char * x3)
+{
+  return nsm_extend_digest(x0, x1, x2, x3);
+}
+#ifndef PYPY_VERSION
+static PyObject *
+_cffi_f_nsm_extend_digest(PyObject *self, PyObject *args)
+{
+  unsigned char const * x0;
+  unsigned char const * x1;
+  size_t x2;
+  unsigned char * x3;
+  Py_ssize_t datasize;
+  struct _cffi_freeme_s *large_args_free = NULL;
+  int result;
+  PyObject *pyresult;
+  PyObject *arg0;
+  PyObject *arg1;
+  PyObject *arg2;
+  PyObject *arg3;
+
+  if (!PyArg_UnpackTuple(args, "nsm_extend_digest", 4, 4, &arg0, &arg1, &arg2, &arg3))
+    return NULL;
+
+  datasize = _cffi_prepare_pointer_call_argument(
+      _cffi_type(13), arg0, (char **)&x0);
+  if (datasize != 0) {
+    x0 = ((size_t)datasize) <= 640 ? (unsigned char const *)alloca((size_t)datasize) : NULL;
+    if (_cffi_convert_array_argument(_cffi_type(13), arg0, (char **)&x0,
+            datasize, &large_args_free) < 0)
+      return NULL;
+  }
+
+  datasize = _cffi_prepare_pointer_call_argument(
+      _cffi_type(13), arg1, (char **)&x1);
+  if (datasize != 0) {
+    x1 = ((size_t)datasize) <= 640 ? (unsigned char const *)alloca((size_t)datasize) : NULL;
+    if (_cffi_convert_array_argument(_cffi_type(13), arg1, (char **)&x1,
+            datasize, &large_args_free) < 0)
+      return NULL;
+  }
+
+  x2 = _cffi_to_c_int(arg2, size_t);
+  if (x2 == (size_t)-1 && PyErr_Occurred())
+    return NULL;
+
+  datasize = _cffi_prepare_pointer_call_argument(
+      _cffi_type(26), arg3, (char **)&x3);
+  if (datasize != 0) {
+    x3 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
+    if (_cffi_convert_array_argument(_cffi_type(26), arg3, (char **)&x3,
+            datasize, &large_args_free) < 0)
+      return NULL;
+  }
+
+  Py_BEGIN_ALLOW_THREADS
+  _cffi_restore_errno();
+  { result = nsm_extend_digest(x0, x1, x2, x3); }
+  _cffi_save_errno();
+  Py_END_ALLOW_THREADS
+
+  (void)self; /* unused */
+  pyresult = _cffi_from_c_int(result, int);
+  if (large_args_free != NULL) _cffi_free_array_arguments(large_args_free);
+  return pyresult;
+}
+#else
+#  define _cffi_f_nsm_extend_digest _cffi_d_nsm_extend_digest
+#endif
+
 static int _cffi_d_nsm_extend_pcr(nsm_session * x0, uint32_t x1, unsigned char const * x2, size_t x3, unsigned char * x4)
 {
   return nsm_extend_pcr(x0, x1, x2, x3, x4);
@@ -944,10 +1027,10 @@ _cffi_f_nsm_extend_pcr(PyObject *self, PyObject *args)
     return NULL;
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(21), arg4, (char **)&x4);
+      _cffi_type(26), arg4, (char **)&x4);
   if (datasize != 0) {
     x4 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(21), arg4, (char **)&x4,
+    if (_cffi_convert_array_argument(_cffi_type(26), arg4, (char **)&x4,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -999,10 +1082,10 @@ _cffi_f_nsm_get_random(PyObject *self, PyObject *args)
   }
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(21), arg1, (char **)&x1);
+      _cffi_type(26), arg1, (char **)&x1);
   if (datasize != 0) {
     x1 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(21), arg1, (char **)&x1,
+    if (_cffi_convert_array_argument(_cffi_type(26), arg1, (char **)&x1,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -1154,10 +1237,10 @@ _cffi_f_nsm_locked_flags(PyObject *self, PyObject *args)
   }
 
   datasize = _cffi_prepare_pointer_call_argument(
-      _cffi_type(21), arg1, (char **)&x1);
+      _cffi_type(26), arg1, (char **)&x1);
   if (datasize != 0) {
     x1 = ((size_t)datasize) <= 640 ? (unsigned char *)alloca((size_t)datasize) : NULL;
-    if (_cffi_convert_array_argument(_cffi_type(21), arg1, (char **)&x1,
+    if (_cffi_convert_array_argument(_cffi_type(26), arg1, (char **)&x1,
             datasize, &large_args_free) < 0)
       return NULL;
   }
@@ -1211,7 +1294,7 @@ _cffi_f_nsm_module_id(PyObject *self, PyObject *arg0)
   Py_END_ALLOW_THREADS
 
   (void)self; /* unused */
-  pyresult = _cffi_from_c_pointer((char *)result, _cffi_type(56));
+  pyresult = _cffi_from_c_pointer((char *)result, _cffi_type(67));
   if (large_args_free != NULL) _cffi_free_array_arguments(large_args_free);
   return pyresult;
 }
@@ -1470,6 +1553,65 @@ _cffi_f_nsm_set_certificate(PyObject *self, PyObject *args)
 #  define _cffi_f_nsm_set_certificate _cffi_d_nsm_set_certificate
 #endif
 
+static int _cffi_d_nsm_set_pcr(nsm_session * x0, uint32_t x1, unsigned char const * x2)
+{
+  return nsm_set_pcr(x0, x1, x2);
+}
+#ifndef PYPY_VERSION
+static PyObject *
+_cffi_f_nsm_set_pcr(PyObject *self, PyObject *args)
+{
+  nsm_session * x0;
+  uint32_t x1;
+  unsigned char const * x2;
+  Py_ssize_t datasize;
+  struct _cffi_freeme_s *large_args_free = NULL;
+  int result;
+  PyObject *pyresult;
+  PyObject *arg0;
+  PyObject *arg1;
+  PyObject *arg2;
+
+  if (!PyArg_UnpackTuple(args, "nsm_set_pcr", 3, 3, &arg0, &arg1, &arg2))
+    return NULL;
+
+  datasize = _cffi_prepare_pointer_call_argument(
+      _cffi_type(4), arg0, (char **)&x0);
+  if (datasize != 0) {
+    x0 = ((size_t)datasize) <= 640 ? (nsm_session *)alloca((size_t)datasize) : NULL;
+    if (_cffi_convert_array_argument(_cffi_type(4), arg0, (char **)&x0,
+            datasize, &large_args_free) < 0)
+      return NULL;
+  }
+
+  x1 = _cffi_to_c_int(arg1, uint32_t);
+  if (x1 == (uint32_t)-1 && PyErr_Occurred())
+    return NULL;
+
+  datasize = _cffi_prepare_pointer_call_argument(
+      _cffi_type(13), arg2, (char **)&x2);
+  if (datasize != 0) {
+    x2 = ((size_t)datasize) <= 640 ? (unsigned char const *)alloca((size_t)datasize) : NULL;
+    if (_cffi_convert_array_argument(_cffi_type(13), arg2, (char **)&x2,
+            datasize, &large_args_free) < 0)
+      return NULL;
+  }
+
+  Py_BEGIN_ALLOW_THREADS
+  _cffi_restore_errno();
+  { result = nsm_set_pcr(x0, x1, x2); }
+  _cffi_save_errno();
+  Py_END_ALLOW_THREADS
+
+  (void)self; /* unused */
+  pyresult = _cffi_from_c_int(result, int);
+  if (large_args_free != NULL) _cffi_free_array_arguments(large_args_free);
+  return pyresult;
+}
+#else
+#  define _cffi_f_nsm_set_pcr _cffi_d_nsm_set_pcr
+#endif
+
 static int _cffi_const_NSM_ERR_CERT_MISSING(unsigned long long *o)
 {
   int n = (NSM_ERR_CERT_MISSING) <= 0;
@@ -1541,51 +1683,53 @@ static const struct _cffi_global_s _cffi_globals[] = {
   { "NSM_ERR_LOCKED", (void *)_cffi_const_NSM_ERR_LOCKED, _CFFI_OP(_CFFI_OP_CONSTANT_INT, -1), (void *)0 },
   { "NSM_ERR_NO_MEMORY", (void *)_cffi_const_NSM_ERR_NO_MEMORY, _CFFI_OP(_CFFI_OP_CONSTANT_INT, -1), (void *)0 },
   { "NSM_OK", (void *)_cffi_const_NSM_OK, _CFFI_OP(_CFFI_OP_CONSTANT_INT, -1), (void *)0 },
-  { "nsm_attestation_digest", (void *)_cffi_f_nsm_attestation_digest, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 42), (void *)_cffi_d_nsm_attestation_digest },
-  { "nsm_describe_certificate", (void *)_cffi_f_nsm_describe_certificate, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 36), (void *)_cffi_d_nsm_describe_certificate },
-  { "nsm_describe_pcr", (void *)_cffi_f_nsm_describe_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 31), (void *)_cffi_d_nsm_describe_pcr },
-  { "nsm_extend_pcr", (void *)_cffi_f_nsm_extend_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 16), (void *)_cffi_d_nsm_extend_pcr },
-  { "nsm_get_random", (void *)_cffi_f_nsm_get_random, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 23), (void *)_cffi_d_nsm_get_random },
+  { "nsm_attestation_digest", (void *)_cffi_f_nsm_attestation_digest, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 47), (void *)_cffi_d_nsm_attestation_digest },
+  { "nsm_describe_certificate", (void *)_cffi_f_nsm_describe_certificate, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 41), (void *)_cffi_d_nsm_describe_certificate },
+  { "nsm_describe_pcr", (void *)_cffi_f_nsm_describe_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 36), (void *)_cffi_d_nsm_describe_pcr },
+  { "nsm_extend_digest", (void *)_cffi_f_nsm_extend_digest, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 56), (void *)_cffi_d_nsm_extend_digest },
+  { "nsm_extend_pcr", (void *)_cffi_f_nsm_extend_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 21), (void *)_cffi_d_nsm_extend_pcr },
+  { "nsm_get_random", (void *)_cffi_f_nsm_get_random, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 28), (void *)_cffi_d_nsm_get_random },
   { "nsm_lock_pcr", (void *)_cffi_f_nsm_lock_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 6), (void *)_cffi_d_nsm_lock_pcr },
   { "nsm_lock_range", (void *)_cffi_f_nsm_lock_range, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 6), (void *)_cffi_d_nsm_lock_range },
-  { "nsm_locked_flags", (void *)_cffi_f_nsm_locked_flags, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 46), (void *)_cffi_d_nsm_locked_flags },
+  { "nsm_locked_flags", (void *)_cffi_f_nsm_locked_flags, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 51), (void *)_cffi_d_nsm_locked_flags },
   { "nsm_module_id", (void *)_cffi_f_nsm_module_id, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 0), (void *)_cffi_d_nsm_module_id },
   { "nsm_remove_certificate", (void *)_cffi_f_nsm_remove_certificate, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 6), (void *)_cffi_d_nsm_remove_certificate },
   { "nsm_session_close", (void *)_cffi_f_nsm_session_close, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 3), (void *)_cffi_d_nsm_session_close },
-  { "nsm_session_free", (void *)_cffi_f_nsm_session_free, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 53), (void *)_cffi_d_nsm_session_free },
-  { "nsm_session_is_closed", (void *)_cffi_f_nsm_session_is_closed, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 28), (void *)_cffi_d_nsm_session_is_closed },
-  { "nsm_session_new", (void *)_cffi_f_nsm_session_new, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_N, 51), (void *)_cffi_d_nsm_session_new },
-  { "nsm_set_certificate", (void *)_cffi_f_nsm_set_certificate, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 10), (void *)_cffi_d_nsm_set_certificate },
+  { "nsm_session_free", (void *)_cffi_f_nsm_session_free, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 64), (void *)_cffi_d_nsm_session_free },
+  { "nsm_session_is_closed", (void *)_cffi_f_nsm_session_is_closed, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_O, 33), (void *)_cffi_d_nsm_session_is_closed },
+  { "nsm_session_new", (void *)_cffi_f_nsm_session_new, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_N, 62), (void *)_cffi_d_nsm_session_new },
+  { "nsm_set_certificate", (void *)_cffi_f_nsm_set_certificate, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 15), (void *)_cffi_d_nsm_set_certificate },
+  { "nsm_set_pcr", (void *)_cffi_f_nsm_set_pcr, _CFFI_OP(_CFFI_OP_CPYTHON_BLTN_V, 10), (void *)_cffi_d_nsm_set_pcr },
 };
 
 static const struct _cffi_field_s _cffi_fields[] = {
   { "closed", offsetof(nsm_session, closed),
               sizeof(((nsm_session *)0)->closed),
-              _CFFI_OP(_CFFI_OP_NOOP, 60) },
+              _CFFI_OP(_CFFI_OP_NOOP, 71) },
   { "module_id", offsetof(nsm_session, module_id),
                  sizeof(((nsm_session *)0)->module_id),
-                 _CFFI_OP(_CFFI_OP_NOOP, 58) },
+                 _CFFI_OP(_CFFI_OP_NOOP, 69) },
   { "pcrs", offsetof(nsm_session, pcrs),
             sizeof(((nsm_session *)0)->pcrs),
-            _CFFI_OP(_CFFI_OP_NOOP, 69) },
+            _CFFI_OP(_CFFI_OP_NOOP, 80) },
   { "pcr_locks", offsetof(nsm_session, pcr_locks),
                  sizeof(((nsm_session *)0)->pcr_locks),
-                 _CFFI_OP(_CFFI_OP_NOOP, 67) },
+                 _CFFI_OP(_CFFI_OP_NOOP, 78) },
   { "cert_data", offsetof(nsm_session, cert_data),
                  sizeof(((nsm_session *)0)->cert_data),
-                 _CFFI_OP(_CFFI_OP_NOOP, 64) },
+                 _CFFI_OP(_CFFI_OP_NOOP, 75) },
   { "cert_len", offsetof(nsm_session, cert_len),
                 sizeof(((nsm_session *)0)->cert_len),
-                _CFFI_OP(_CFFI_OP_NOOP, 62) },
+                _CFFI_OP(_CFFI_OP_NOOP, 73) },
 };
 
 static const struct _cffi_struct_union_s _cffi_struct_unions[] = {
-  { "$nsm_session", 61, _CFFI_F_CHECK_FIELDS,
+  { "$nsm_session", 72, _CFFI_F_CHECK_FIELDS,
     sizeof(nsm_session), offsetof(struct _cffi_align_typedef_nsm_session, y), 0, 6 },
 };
 
 static const struct _cffi_typename_s _cffi_typenames[] = {
-  { "nsm_session", 61 },
+  { "nsm_session", 72 },
 };
 
 static const struct _cffi_type_context_s _cffi_type_context = {
@@ -1595,12 +1739,12 @@ static const struct _cffi_type_context_s _cffi_type_context = {
   _cffi_struct_unions,
   NULL,  /* no enums */
   _cffi_typenames,
-  22,  /* num_globals */
+  24,  /* num_globals */
   1,  /* num_struct_unions */
   0,  /* num_enums */
   1,  /* num_typenames */
   NULL,  /* no includes */
-  72,  /* num_types */
+  83,  /* num_types */
   0,  /* flags */
 };
 
@@ -3,8 +3,8 @@ import _cffi_backend
 
 ffi = _cffi_backend.FFI('aws_nitro_enclaves.nsm._native',
     _version = 0x2601,
-    _types = b'\x00\x00\x38\x0D\x00\x00\x3D\x03\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x3D\x03\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x42\x03\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x0D\x11\x00\x00\x1C\x01\x00\x00\x42\x03\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x04\x11\x00\x00\x15\x11\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x01\x11\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x01\x11\x00\x00\x16\x01\x00\x00\x15\x11\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x01\x11\x00\x00\x16\x01\x00\x00\x0D\x03\x00\x00\x0E\x03\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x01\x11\x00\x00\x15\x11\x00\x00\x00\x0F\x00\x00\x3C\x0D\x00\x00\x01\x11\x00\x00\x15\x11\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x04\x0D\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x00\x0F\x00\x00\x39\x03\x00\x00\x02\x01\x00\x00\x39\x05\x00\x00\x00\x21\x00\x00\x07\x01\x00\x00\x00\x09\x00\x00\x0E\x05\x00\x00\x00\x04\x00\x00\x15\x05\x00\x00\x00\x04\x00\x00\x04\x01\x00\x00\x42\x05\x00\x00\x00\x20\x00\x00\x43\x05\x00\x00\x00\x20\x00\x00\x00\x01',
-    _globals = (b'\xFF\xFF\xFF\x1FNSM_ERR_CERT_MISSING',4,b'\xFF\xFF\xFF\x1FNSM_ERR_CLOSED',6,b'\xFF\xFF\xFF\x1FNSM_ERR_INVALID_LENGTH',3,b'\xFF\xFF\xFF\x1FNSM_ERR_INVALID_SLOT',1,b'\xFF\xFF\xFF\x1FNSM_ERR_LOCKED',2,b'\xFF\xFF\xFF\x1FNSM_ERR_NO_MEMORY',5,b'\xFF\xFF\xFF\x1FNSM_OK',0,b'\x00\x00\x2A\x23nsm_attestation_digest',0,b'\x00\x00\x24\x23nsm_describe_certificate',0,b'\x00\x00\x1F\x23nsm_describe_pcr',0,b'\x00\x00\x10\x23nsm_extend_pcr',0,b'\x00\x00\x17\x23nsm_get_random',0,b'\x00\x00\x06\x23nsm_lock_pcr',0,b'\x00\x00\x06\x23nsm_lock_range',0,b'\x00\x00\x2E\x23nsm_locked_flags',0,b'\x00\x00\x00\x23nsm_module_id',0,b'\x00\x00\x06\x23nsm_remove_certificate',0,b'\x00\x00\x03\x23nsm_session_close',0,b'\x00\x00\x35\x23nsm_session_free',0,b'\x00\x00\x1C\x23nsm_session_is_closed',0,b'\x00\x00\x33\x23nsm_session_new',0,b'\x00\x00\x0A\x23nsm_set_certificate',0),
-    _struct_unions = ((b'\x00\x00\x00\x3D\x00\x00\x00\x02$nsm_session',b'\x00\x00\x3C\x11closed',b'\x00\x00\x3A\x11module_id',b'\x00\x00\x45\x11pcrs',b'\x00\x00\x43\x11pcr_locks',b'\x00\x00\x40\x11cert_data',b'\x00\x00\x3E\x11cert_len'),),
-    _typenames = (b'\x00\x00\x00\x3Dnsm_session',),
+    _types = b'\x00\x00\x43\x0D\x00\x00\x48\x03\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x48\x03\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x4D\x03\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x0D\x11\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x16\x01\x00\x00\x0D\x11\x00\x00\x1C\x01\x00\x00\x4D\x03\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x04\x11\x00\x00\x1A\x11\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x01\x11\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x01\x11\x00\x00\x16\x01\x00\x00\x1A\x11\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x01\x11\x00\x00\x16\x01\x00\x00\x0D\x03\x00\x00\x13\x03\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x01\x11\x00\x00\x1A\x11\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x01\x11\x00\x00\x1A\x11\x00\x00\x1C\x01\x00\x00\x00\x0F\x00\x00\x47\x0D\x00\x00\x0D\x11\x00\x00\x0D\x11\x00\x00\x1C\x01\x00\x00\x1A\x11\x00\x00\x00\x0F\x00\x00\x04\x0D\x00\x00\x00\x0F\x00\x00\x52\x0D\x00\x00\x04\x11\x00\x00\x00\x0F\x00\x00\x44\x03\x00\x00\x02\x01\x00\x00\x44\x05\x00\x00\x00\x21\x00\x00\x07\x01\x00\x00\x00\x09\x00\x00\x13\x05\x00\x00\x00\x04\x00\x00\x1A\x05\x00\x00\x00\x04\x00\x00\x04\x01\x00\x00\x4D\x05\x00\x00\x00\x20\x00\x00\x4E\x05\x00\x00\x00\x20\x00\x00\x00\x01',
+    _globals = (b'\xFF\xFF\xFF\x1FNSM_ERR_CERT_MISSING',4,b'\xFF\xFF\xFF\x1FNSM_ERR_CLOSED',6,b'\xFF\xFF\xFF\x1FNSM_ERR_INVALID_LENGTH',3,b'\xFF\xFF\xFF\x1FNSM_ERR_INVALID_SLOT',1,b'\xFF\xFF\xFF\x1FNSM_ERR_LOCKED',2,b'\xFF\xFF\xFF\x1FNSM_ERR_NO_MEMORY',5,b'\xFF\xFF\xFF\x1FNSM_OK',0,b'\x00\x00\x2F\x23nsm_attestation_digest',0,b'\x00\x00\x29\x23nsm_describe_certificate',0,b'\x00\x00\x24\x23nsm_describe_pcr',0,b'\x00\x00\x38\x23nsm_extend_digest',0,b'\x00\x00\x15\x23nsm_extend_pcr',0,b'\x00\x00\x1C\x23nsm_get_random',0,b'\x00\x00\x06\x23nsm_lock_pcr',0,b'\x00\x00\x06\x23nsm_lock_range',0,b'\x00\x00\x33\x23nsm_locked_flags',0,b'\x00\x00\x00\x23nsm_module_id',0,b'\x00\x00\x06\x23nsm_remove_certificate',0,b'\x00\x00\x03\x23nsm_session_close',0,b'\x00\x00\x40\x23nsm_session_free',0,b'\x00\x00\x21\x23nsm_session_is_closed',0,b'\x00\x00\x3E\x23nsm_session_new',0,b'\x00\x00\x0F\x23nsm_set_certificate',0,b'\x00\x00\x0A\x23nsm_set_pcr',0),
+    _struct_unions = ((b'\x00\x00\x00\x48\x00\x00\x00\x02$nsm_session',b'\x00\x00\x47\x11closed',b'\x00\x00\x45\x11module_id',b'\x00\x00\x50\x11pcrs',b'\x00\x00\x4E\x11pcr_locks',b'\x00\x00\x4B\x11cert_data',b'\x00\x00\x49\x11cert_len'),),
+    _typenames = (b'\x00\x00\x00\x48nsm_session',),
 )
@@ -0,0 +1,39 @@
+"""Helpers for converting certificates between PEM and DER."""
+
+from __future__ import annotations
+
+import base64
+import binascii
+import re
+from typing import List
+
+from .errors import NsmCertificateError
+
+_PEM_CERTIFICATE = re.compile(
+    rb"-----BEGIN CERTIFICATE-----(.*?)-----END CERTIFICATE-----", re.DOTALL
+)
+
+
+def decode_certificates(bundle: bytes) -> List[bytes]:
+    """Return the DER bytes of every PEM certificate block in ``bundle``, in order."""
+
+    certificates = []
+    for index, match in enumerate(_PEM_CERTIFICATE.finditer(bundle)):
+        body = b"".join(match.group(1).split())
+        try:
+            certificates.append(base64.b64decode(body, validate=True))
+        except binascii.Error as exc:
+            raise NsmCertificateError(
+                f"PEM certificate {index} has an invalid base64 body", cause=exc
+            )
+    return certificates
+
+
+def encode_certificate(der: bytes) -> bytes:
+    """Wrap DER certificate bytes in a PEM block with 64-column base64 lines."""
+
+    body = base64.b64encode(der)
+    lines = [body[offset : offset + 64] for offset in range(0, len(body), 64)]
+    return b"\n".join(
+        [b"-----BEGIN CERTIFICATE-----", *lines, b"-----END CERTIFICATE-----", b""]
+    )
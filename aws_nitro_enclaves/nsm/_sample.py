@@ -0,0 +1,50 @@
+"""Deterministic attestation test vector for offline verifier development."""
+
+from __future__ import annotations
+
+from typing import Dict
+
+from . import _transport
+
+SAMPLE_MODULE_ID = "0123456789abcdef0123456789abcdef"
+SAMPLE_TIMESTAMP = 1_700_000_000
+SAMPLE_USER_DATA = b"sample-user-data"
+SAMPLE_NONCE = b"sample-nonce"
+
+# Ed25519 public key of SAMPLE_CERTIFICATE; the private key is SHA-256 of
+# b"aws-nitro-enclaves-sample".
+SAMPLE_PUBLIC_KEY = bytes.fromhex(
+    "a12564afbbdab11ffa642364ba2671f5deee6c45556acb0b0106a48bebe681a8"
+)
+
+# Self-signed DER certificate, CN=sample-enclave, serial 1, valid 2024-01-01
+# to 2034-01-01 (UTC).
+SAMPLE_CERTIFICATE = bytes.fromhex(
+    "3081de308191a003020102020101300506032b657030193117301506035504030c0e73616d70"
+    "6c652d656e636c617665301e170d3234303130313030303030305a170d3334303130313030"
+    "303030305a30193117301506035504030c0e73616d706c652d656e636c617665302a300506"
+    "032b6570032100a12564afbbdab11ffa642364ba2671f5deee6c45556acb0b0106a48bebe6"
+    "81a8300506032b65700341009b2fcce27a6e50418bd7df5e239aa49800d81766e5727e10b8"
+    "a3805c77601f6f2bf17a35cf75fdfd6aaf7b830df324b2fad6ad9ec308509208a6516327ca"
+    "7206"
+)
+
+
+def sample_attestation() -> Dict[str, object]:
+    """Return a fixed, fully populated attestation payload.
+
+    PCR ``i`` is 32 bytes of value ``i`` for every slot, PCRs 0-7 are locked and
+    the remaining fields use the ``SAMPLE_*`` constants in this module. The
+    result is identical on every call and does not touch a device.
+    """
+
+    return _transport.build_attestation_payload(
+        {slot: bytes([slot]) * _transport.PCR_DIGEST_LEN for slot in range(_transport.PCR_SLOTS)},
+        SAMPLE_MODULE_ID,
+        locked_pcrs=range(8),
+        certificate=SAMPLE_CERTIFICATE,
+        user_data=SAMPLE_USER_DATA,
+        public_key=SAMPLE_PUBLIC_KEY,
+        nonce=SAMPLE_NONCE,
+        timestamp=SAMPLE_TIMESTAMP,
+    )
@@ -66,6 +66,16 @@ static void simple_hash(const unsigned char *data, size_t length, unsigned char
     }
 }
 
+static void release_certificate(nsm_session *session, size_t slot) {
+    volatile unsigned char *data = session->cert_data[slot];
+    for (size_t i = 0; i < session->cert_len[slot]; ++i) {
+        data[i] = 0;
+    }
+    free(session->cert_data[slot]);
+    session->cert_data[slot] = NULL;
+    session->cert_len[slot] = 0;
+}
+
 nsm_session *nsm_session_new(void) {
     nsm_session *session = (nsm_session *)calloc(1, sizeof(nsm_session));
     if (!session) {
@@ -90,7 +100,7 @@ void nsm_session_free(nsm_session *session) {
     }
     for (size_t i = 0; i < CERT_SLOTS; ++i) {
         if (session->cert_data[i]) {
-            free(session->cert_data[i]);
+            release_certificate(session, i);
         }
     }
     free(session);
@@ -154,6 +164,21 @@ int nsm_describe_pcr(const nsm_session *session, uint32_t slot, unsigned char *o
     return NSM_OK;
 }
 
+int nsm_extend_digest(const unsigned char *current, const unsigned char *data, size_t length, unsigned char *out) {
+    if (!current || !data || length == 0) {
+        return NSM_ERR_INVALID_LENGTH;
+    }
+    unsigned char *buffer = (unsigned char *)malloc(PCR_DIGEST_LEN + length);
+    if (!buffer) {
+        return NSM_ERR_NO_MEMORY;
+    }
+    memcpy(buffer, current, PCR_DIGEST_LEN);
+    memcpy(buffer + PCR_DIGEST_LEN, data, length);
+    simple_hash(buffer, PCR_DIGEST_LEN + length, out);
+    free(buffer);
+    return NSM_OK;
+}
+
 int nsm_extend_pcr(nsm_session *session, uint32_t slot, const unsigned char *data, size_t length, unsigned char *out) {
     if (ensure_open(session) != NSM_OK) {
         return NSM_ERR_CLOSED;
@@ -167,15 +192,28 @@ int nsm_extend_pcr(nsm_session *session, uint32_t slot, const unsigned char *dat
     if (session->pcr_locks[slot]) {
         return NSM_ERR_LOCKED;
     }
-    unsigned char *buffer = (unsigned char *)malloc(PCR_DIGEST_LEN + length);
-    if (!buffer) {
-        return NSM_ERR_NO_MEMORY;
+    int code = nsm_extend_digest(session->pcrs[slot], data, length, session->pcrs[slot]);
+    if (code != NSM_OK) {
+        return code;
     }
-    memcpy(buffer, session->pcrs[slot], PCR_DIGEST_LEN);
-    memcpy(buffer + PCR_DIGEST_LEN, data, length);
-    simple_hash(buffer, PCR_DIGEST_LEN + length, session->pcrs[slot]);
     memcpy(out, session->pcrs[slot], PCR_DIGEST_LEN);
-    free(buffer);
+    return NSM_OK;
+}
+
+int nsm_set_pcr(nsm_session *session, uint32_t slot, const unsigned char *digest) {
+    if (ensure_open(session) != NSM_OK) {
+        return NSM_ERR_CLOSED;
+    }
+    if (validate_slot(slot) != NSM_OK) {
+        return NSM_ERR_INVALID_SLOT;
+    }
+    if (!digest) {
+        return NSM_ERR_INVALID_LENGTH;
+    }
+    if (session->pcr_locks[slot]) {
+        return NSM_ERR_LOCKED;
+    }
+    memcpy(session->pcrs[slot], digest, PCR_DIGEST_LEN);
     return NSM_OK;
 }
 
@@ -219,7 +257,7 @@ int nsm_set_certificate(nsm_session *session, uint32_t slot, const unsigned char
     }
     memcpy(copy, data, length);
     if (session->cert_data[slot]) {
-        free(session->cert_data[slot]);
+        release_certificate(session, slot);
     }
     session->cert_data[slot] = copy;
     session->cert_len[slot] = length;
@@ -251,9 +289,7 @@ int nsm_remove_certificate(nsm_session *session, uint32_t slot) {
     if (!session->cert_data[slot]) {
         return NSM_ERR_CERT_MISSING;
     }
-    free(session->cert_data[slot]);
-    session->cert_data[slot] = NULL;
-    session->cert_len[slot] = 0;
+    release_certificate(session, slot);
     return NSM_OK;
 }
 
@@ -3,11 +3,16 @@
 from __future__ import annotations
 
 import hashlib
+import hmac
+import os
+import re
+import stat
+import threading
 import time
 from contextlib import AbstractContextManager
 from importlib import metadata
 from pathlib import Path
-from typing import Any, Dict, Iterable, Optional, Sequence, cast
+from typing import Any, Dict, Iterable, List, Mapping, Optional, Sequence, Tuple, cast
 
 from . import _native_typing as _native
 from .errors import (
@@ -16,9 +21,11 @@ from .errors import (
     NsmDeviceNotFoundError,
     NsmError,
     NsmInvalidPcrError,
+    NsmModuleIdError,
     NsmPcrLockedError,
     NsmRandomError,
     NsmSessionClosedError,
+    NsmSessionFrozenError,
 )
 
 # expose typed helpers locally
@@ -31,6 +38,17 @@ DEFAULT_DEVICE_PATH = "/dev/nsm"
 PCR_SLOTS = 32
 PCR_DIGEST_LEN = 32
 CERTIFICATE_SLOTS = 4
+MAX_DERIVED_KEY_LEN = 255 * PCR_DIGEST_LEN
+MODULE_ID_MAX_LEN = 64
+_MODULE_ID_PATTERN = re.compile(r"[0-9a-f]+")
+DIGEST_ALGORITHM = "sha256"
+ATTESTATION_DIGEST_LEN = hashlib.new(DIGEST_ALGORITHM).digest_size
+# PCRs are extended with the shim's non-cryptographic simple_hash, not a SHA-2
+# function, so verifiers must not recompute them as SHA256/SHA384.
+PCR_DIGEST_ALGORITHM = "simulated"
+# The bundled shim draws random bytes from libc rand() seeded with the clock,
+# so it reports itself as "seeded" rather than an OS-backed "os" source.
+RNG_BACKEND = "seeded"
 
 
 class NativeUnavailableError(NsmError):
@@ -64,7 +82,10 @@ def _raise_error(
         if context == "random":
             raise NsmRandomError("Random length must be greater than zero")
         if context == "certificate":
-            raise NsmCertificateError("Certificate payload must not be empty")
+            raise NsmCertificateError(
+                f"Certificate payload for slot {slot if slot is not None else '?'} "
+                "must not be empty"
+            )
         raise NsmError("Invalid length for native operation")
     if code == lib.NSM_ERR_LOCKED:
         raise NsmPcrLockedError(
@@ -102,6 +123,18 @@ class NsmTransport(AbstractContextManager):
             raise NsmError("Failed to initialise NSM session")
         self._session = ffi.gc(raw_session, lib.nsm_session_free)
         self._certificates: Dict[int, bool] = {}
+        self._certificate_labels: Dict[int, str] = {}
+        # Held by every certificate read and write so replace_certificates is
+        # atomic with respect to readers, including attestation.
+        self._certificate_lock = threading.RLock()
+        self._module_id_override: Optional[str] = None
+        self._last_modified = time.monotonic()
+        self._frozen = False
+        self._attestation_count = 0
+        self._static_description: Optional[Dict[str, object]] = None
+        # The shim's RNG is seeded from the clock, so sessions opened in the same
+        # second would share a secret; the OS CSPRNG is used whatever rng_backend is.
+        self._derivation_secret = os.urandom(PCR_DIGEST_LEN)
 
     def __enter__(self) -> "NsmTransport":
         return self
@@ -123,12 +156,42 @@ class NsmTransport(AbstractContextManager):
     def device_path(self) -> str:
         return self._device_path
 
+    @property
+    def attestation_count(self) -> int:
+        return self._attestation_count
+
+    @property
+    def rng_backend(self) -> str:
+        return RNG_BACKEND
+
+    @property
+    def is_simulated(self) -> bool:
+        # PCRs, locks and certificates live in the shim's memory whatever the
+        # device path points at, so this transport never talks to a real NSM.
+        return True
+
+    @property
+    def is_frozen(self) -> bool:
+        return self._frozen
+
+    def freeze(self) -> None:
+        self._frozen = True
+
     def get_random(self, length: int) -> bytes:
         buffer = ffi.new("unsigned char[]", length)
         code = lib.nsm_get_random(self._session, buffer, length)
         _raise_error(code, context="random")
         return _native.buf_to_bytes(buffer, length)
 
+    def get_random_buffer(self, length: int) -> bytearray:
+        out = bytearray(length)
+        code = lib.nsm_get_random(self._session, ffi.from_buffer(out), length)
+        _raise_error(code, context="random")
+        return out
+
+    def derive_key(self, context: bytes, length: int) -> bytes:
+        return _hkdf_expand(self._derivation_secret, context, length)
+
     def describe_pcr_raw(self, slot: int) -> Dict[str, Any]:
         buffer = ffi.new("unsigned char[]", PCR_DIGEST_LEN)
         code = lib.nsm_describe_pcr(self._session, slot, buffer)
@@ -144,57 +207,138 @@ class NsmTransport(AbstractContextManager):
         raw = self.describe_pcr_raw(slot)
         return cast(bytes, raw["digest"])
 
-    def extend_pcr(self, slot: int, data: bytes) -> bytes:
+    def describe_pcr_bank(self) -> List[bytes]:
+        return [self.describe_pcr(index) for index in range(PCR_SLOTS)]
+
+    def extend_pcr(self, slot: int, data: bytes, tag: Optional[bytes] = None) -> bytes:
+        self._ensure_mutable()
+        if tag:
+            # Length-prefixing the tag keeps (b"boot:", b"x") distinct from b"boot:x".
+            data = len(tag).to_bytes(2, "big") + tag + data
         buffer = ffi.new("unsigned char[]", PCR_DIGEST_LEN)
         code = lib.nsm_extend_pcr(self._session, slot, data, len(data), buffer)
         _raise_error(code, context="pcr", slot=slot)
+        self._touch()
         return _native.buf_to_bytes(buffer, PCR_DIGEST_LEN)
 
+    def set_pcr(self, slot: int, digest: bytes) -> None:
+        self._ensure_mutable()
+        if len(digest) != PCR_DIGEST_LEN:
+            raise NsmError(f"PCR digest must be {PCR_DIGEST_LEN} bytes")
+        code = lib.nsm_set_pcr(self._session, slot, digest)
+        _raise_error(code, context="pcr", slot=slot)
+        self._touch()
+
     def lock_pcr(self, slot: int) -> bool:
+        self._ensure_mutable()
         code = lib.nsm_lock_pcr(self._session, slot)
         _raise_error(code, context="pcr", slot=slot)
+        self._touch()
         return True
 
     def lock_pcrs(self, lock_range: int) -> bool:
+        self._ensure_mutable()
         code = lib.nsm_lock_range(self._session, lock_range)
         _raise_error(code, context="pcr")
+        self._touch()
         return True
 
-    def set_certificate(self, slot: int, certificate: bytes) -> None:
-        code = lib.nsm_set_certificate(
-            self._session,
-            slot,
-            certificate,
-            len(certificate),
-        )
-        _raise_error(code, context="certificate", slot=slot)
-        self._certificates[slot] = True
+    def set_certificate(
+        self, slot: int, certificate: bytes, *, label: Optional[str] = None
+    ) -> None:
+        self._ensure_mutable()
+        with self._certificate_lock:
+            code = lib.nsm_set_certificate(
+                self._session,
+                slot,
+                certificate,
+                len(certificate),
+            )
+            _raise_error(code, context="certificate", slot=slot)
+            self._touch()
+            self._certificates[slot] = True
+            if label is None:
+                self._certificate_labels.pop(slot, None)
+            else:
+                self._certificate_labels[slot] = label
 
     def describe_certificate(self, slot: int) -> bytes:
         out_ptr = ffi.new("const unsigned char **")
         out_len = ffi.new("size_t *")
-        code = lib.nsm_describe_certificate(self._session, slot, out_ptr, out_len)
-        _raise_error(code, context="certificate", slot=slot)
-        data = _native.buf_to_bytes(out_ptr[0], out_len[0])
-        self._certificates[slot] = True
+        with self._certificate_lock:
+            code = lib.nsm_describe_certificate(self._session, slot, out_ptr, out_len)
+            _raise_error(code, context="certificate", slot=slot)
+            data = _native.buf_to_bytes(out_ptr[0], out_len[0])
+            self._certificates[slot] = True
         return data
 
     def remove_certificate(self, slot: int) -> None:
-        code = lib.nsm_remove_certificate(self._session, slot)
-        _raise_error(code, context="certificate", slot=slot)
-        self._certificates.pop(slot, None)
+        self._ensure_mutable()
+        with self._certificate_lock:
+            code = lib.nsm_remove_certificate(self._session, slot)
+            _raise_error(code, context="certificate", slot=slot)
+            self._touch()
+            self._certificates.pop(slot, None)
+            self._certificate_labels.pop(slot, None)
+
+    def replace_certificates(
+        self, certificates: Mapping[int, Tuple[bytes, Optional[str]]]
+    ) -> None:
+        """Swap the whole certificate set (slot -> (DER, label)) atomically.
+
+        Readers wait on the certificate lock, so they never observe a partial
+        set. If a write fails the previous set is reinstalled and the error is
+        re-raised.
+        """
+
+        self._ensure_mutable()
+        with self._certificate_lock:
+            previous = {
+                slot: (certificate, self.certificate_label(slot))
+                for slot, certificate in self.describe_certificates().items()
+            }
+            try:
+                self._install_certificates(certificates)
+            except NsmError:
+                self._install_certificates(previous)
+                raise
+
+    def describe_certificates(self) -> Dict[int, bytes]:
+        certificates: Dict[int, bytes] = {}
+        with self._certificate_lock:
+            for slot in range(CERTIFICATE_SLOTS):
+                certificate = self._certificate_or_none(slot)
+                if certificate is not None:
+                    certificates[slot] = certificate
+        return certificates
+
+    def certificate_slots(self) -> List[int]:
+        return list(self.describe_certificates())
+
+    def certificate_label(self, slot: int) -> Optional[str]:
+        with self._certificate_lock:
+            return self._certificate_labels.get(slot)
 
     def describe_nsm(self) -> Dict[str, object]:
-        module_id = self._module_id()
-        locked = [index for index, state in enumerate(self._locked_flags()) if state]
-        return {
-            "module_id": module_id,
-            "device_path": self.device_path,
-            "pcr_slots": PCR_SLOTS,
-            "certificate_slots": CERTIFICATE_SLOTS,
-            "locked_pcrs": locked,
-            "certificates": len(self._certificates),
-        }
+        # Static fields are cached; the volatile ones are refreshed in place so
+        # the key order never changes.
+        if self._static_description is None:
+            self._static_description = {
+                "module_id": self._module_id(),
+                "device_path": self.device_path,
+                "pcr_slots": PCR_SLOTS,
+                "certificate_slots": CERTIFICATE_SLOTS,
+                "locked_pcrs": None,
+                "certificates": None,
+                "digest_algorithm": DIGEST_ALGORITHM,
+                "rng_backend": self.rng_backend,
+                "attestation_count": None,
+            }
+        description = dict(self._static_description)
+        description["locked_pcrs"] = self.locked_slots()
+        description["certificates"] = len(self._certificates)
+        description["attestation_count"] = self._attestation_count
+        return description
 
     def get_attestation(
         self,
@@ -202,52 +346,91 @@ class NsmTransport(AbstractContextManager):
         user_data: Optional[bytes] = None,
         public_key: Optional[bytes] = None,
         nonce: Optional[bytes] = None,
+        channel_binding: Optional[bytes] = None,
+        public_key_format: str = "raw",
+        include_pcrs: bool = True,
+        bind_module_id: bool = False,
+        bind_locks: bool = False,
+        leaf_cert_slot: int = 0,
+        canonical_digest: bool = False,
+        timestamp: Optional[int] = None,
+        include_certificates: bool = True,
+        digest: Optional[bytes] = None,
+        record: bool = True,
     ) -> Dict[str, object]:
         if self.is_closed:
             raise NsmSessionClosedError("NSM session is closed")
 
         try:
-            pcrs = {index: self.describe_pcr(index) for index in range(PCR_SLOTS)}
-            digest = self._attestation_digest(
-                pcrs.values(), user_data, public_key, nonce
+            pcrs = dict(enumerate(self.describe_pcr_bank()))
+            locked = self.locked_slots()
+            certificates: Dict[int, Optional[bytes]] = {}
+            if include_certificates:
+                with self._certificate_lock:
+                    certificates = {
+                        slot: self._certificate_or_none(slot) for slot in range(CERTIFICATE_SLOTS)
+                    }
+            leaf = certificates.pop(leaf_cert_slot, None)
+            payload = build_attestation_payload(
+                pcrs,
+                self._module_id(),
+                locked_pcrs=locked,
+                certificate=leaf,
+                cabundle=[cert for cert in certificates.values() if cert is not None],
+                user_data=user_data,
+                public_key=public_key,
+                nonce=nonce,
+                channel_binding=channel_binding,
+                public_key_format=public_key_format,
+                include_pcrs=include_pcrs,
+                bind_module_id=bind_module_id,
+                bind_locks=bind_locks,
+                canonical_digest=canonical_digest,
+                timestamp=timestamp,
+                digest=digest,
             )
-            locked = [
-                index for index, state in enumerate(self._locked_flags()) if state
-            ]
+        except NsmAttestationError:
+            raise
         except NsmError as exc:
             raise NsmAttestationError("Unable to build attestation payload", cause=exc)
-
-        payload: Dict[str, object] = {
-            "module_id": self._module_id(),
-            "timestamp": int(time.time()),
-            "digest": digest,
-            "pcrs": pcrs,
-            "locked_pcrs": locked,
-            "certificate": self._first_certificate(),
-            "cabundle": None,
-            "user_data": user_data,
-            "public_key": public_key,
-            "nonce": nonce,
-        }
+        if record:
+            self.record_attestation()
         return payload
 
-    def get_attestation_raw(
-        self,
-        *,
-        user_data: Optional[bytes] = None,
-        public_key: Optional[bytes] = None,
-        nonce: Optional[bytes] = None,
-    ) -> Dict[str, object]:
-        return self.get_attestation(
-            user_data=user_data, public_key=public_key, nonce=nonce
-        )
+    def record_attestation(self) -> None:
+        """Count an attestation built with ``record=False`` once it is handed out."""
+
+        self._attestation_count += 1
+
+    def get_attestation_raw(self, **options: Any) -> Dict[str, object]:
+        return self.get_attestation(**options)
+
+    def locked_slots(self) -> List[int]:
+        return [index for index, state in enumerate(self._locked_flags()) if state]
+
+    def seconds_since_last_change(self) -> int:
+        return int(time.monotonic() - self._last_modified)
+
+    def set_module_id(self, module_id: str) -> None:
+        validate_module_id(module_id)
+        self._module_id_override = module_id
+        self._static_description = None
 
     def _module_id(self) -> str:
+        if self._module_id_override is not None:
+            return self._module_id_override
         pointer = lib.nsm_module_id(self._session)
         if pointer == ffi.NULL:
             raise NsmError("NSM session returned an empty module ID")
         return _native.ptr_to_str(pointer)
 
+    def _ensure_mutable(self) -> None:
+        if self._frozen:
+            raise NsmSessionFrozenError("NSM session is frozen")
+
+    def _touch(self) -> None:
+        self._last_modified = time.monotonic()
+
     def _locked_flags(self) -> Sequence[int]:
         buffer = ffi.new("unsigned char[]", PCR_SLOTS)
         code = lib.nsm_locked_flags(self._session, buffer, PCR_SLOTS)
@@ -260,35 +443,253 @@ class NsmTransport(AbstractContextManager):
             raise NsmInvalidPcrError(f"PCR slot {slot} is out of range")
         return bool(flags[slot])
 
-    def _first_certificate(self) -> Optional[bytes]:
-        for slot in range(CERTIFICATE_SLOTS):
-            out_ptr = ffi.new("const unsigned char **")
-            out_len = ffi.new("size_t *")
-            code = lib.nsm_describe_certificate(self._session, slot, out_ptr, out_len)
-            if code == lib.NSM_OK:
-                return _native.buf_to_bytes(out_ptr[0], out_len[0])
-            if code == lib.NSM_ERR_CERT_MISSING:
-                continue
-            _raise_error(code, context="certificate", slot=slot)
-        return None
+    def _install_certificates(
+        self, certificates: Mapping[int, Tuple[bytes, Optional[str]]]
+    ) -> None:
+        for slot in self.certificate_slots():
+            if slot not in certificates:
+                self.remove_certificate(slot)
+        for slot, (certificate, label) in sorted(certificates.items()):
+            self.set_certificate(slot, certificate, label=label)
+
+    def _certificate_or_none(self, slot: int) -> Optional[bytes]:
+        out_ptr = ffi.new("const unsigned char **")
+        out_len = ffi.new("size_t *")
+        code = lib.nsm_describe_certificate(self._session, slot, out_ptr, out_len)
+        if code == lib.NSM_ERR_CERT_MISSING:
+            return None
+        _raise_error(code, context="certificate", slot=slot)
+        return _native.buf_to_bytes(out_ptr[0], out_len[0])
 
     @staticmethod
     def _attestation_digest(
-        pcr_values: Iterable[bytes],
+        pcrs: Mapping[int, bytes],
         user_data: Optional[bytes],
         public_key: Optional[bytes],
         nonce: Optional[bytes],
+        *,
+        module_id: Optional[str] = None,
+        locked_pcrs: Optional[Iterable[int]] = None,
+        canonical: bool = False,
+        channel_binding: Optional[bytes] = None,
+        pcrs_digest: Optional[bytes] = None,
     ) -> bytes:
-        hasher = hashlib.sha256()
-        for value in pcr_values:
-            hasher.update(value)
+        """Hash the attestation preimage.
+
+        The preimage is the concatenation, in order, of:
+
+        1. every PCR digest in ascending slot order; with ``canonical`` each one
+           is instead encoded as ``slot (4 bytes BE) || length (4 bytes BE) || digest``.
+           When ``pcrs_digest`` is given (``include_pcrs=False``) it replaces
+           the individual PCRs;
+        2. the ASCII module ID, only when ``module_id`` is given (``bind_module_id``);
+        3. the lock bitmap, only when ``locked_pcrs`` is given (``bind_locks``):
+           ``PCR_SLOTS // 8`` bytes where slot ``i`` is bit ``i % 8`` (LSB first)
+           of byte ``i // 8``;
+        4. ``user_data``, ``public_key`` and ``nonce``, each skipped when empty;
+        5. ``channel_binding`` as ``length (4 bytes BE) || value``, only when given.
+        """
+
+        hasher = NsmTransport._attestation_hasher(
+            pcrs,
+            module_id=module_id,
+            locked_pcrs=locked_pcrs,
+            canonical=canonical,
+            pcrs_digest=pcrs_digest,
+        )
         if user_data:
             hasher.update(user_data)
+        return NsmTransport._finish_attestation_digest(
+            hasher, public_key, nonce, channel_binding=channel_binding
+        )
+
+    @staticmethod
+    def _attestation_hasher(
+        pcrs: Mapping[int, bytes],
+        *,
+        module_id: Optional[str] = None,
+        locked_pcrs: Optional[Iterable[int]] = None,
+        canonical: bool = False,
+        pcrs_digest: Optional[bytes] = None,
+    ) -> Any:
+        """Return a hasher fed with preimage steps 1-3 of :meth:`_attestation_digest`."""
+
+        hasher = hashlib.new(DIGEST_ALGORITHM)
+        if pcrs_digest is not None:
+            hasher.update(pcrs_digest)
+        else:
+            for slot in sorted(pcrs):
+                value = pcrs[slot]
+                if canonical:
+                    hasher.update(slot.to_bytes(4, "big") + len(value).to_bytes(4, "big"))
+                hasher.update(value)
+        if module_id is not None:
+            hasher.update(module_id.encode("ascii"))
+        if locked_pcrs is not None:
+            bitmap = bytearray(PCR_SLOTS // 8)
+            for slot in locked_pcrs:
+                bitmap[slot // 8] |= 1 << (slot % 8)
+            hasher.update(bytes(bitmap))
+        return hasher
+
+    @staticmethod
+    def _finish_attestation_digest(
+        hasher: Any,
+        public_key: Optional[bytes],
+        nonce: Optional[bytes],
+        *,
+        channel_binding: Optional[bytes] = None,
+    ) -> bytes:
+        """Feed the preimage steps after ``user_data`` into ``hasher`` and return the digest."""
+
         if public_key:
             hasher.update(public_key)
         if nonce:
             hasher.update(nonce)
-        return hasher.digest()
+        if channel_binding is not None:
+            hasher.update(len(channel_binding).to_bytes(4, "big") + channel_binding)
+        return cast(bytes, hasher.digest())
+
+
+def extend_digest(current: bytes, data: bytes) -> bytes:
+    """Return the PCR value that extending ``current`` with ``data`` would produce."""
+
+    if len(current) != PCR_DIGEST_LEN:
+        raise NsmError(f"PCR digest must be {PCR_DIGEST_LEN} bytes")
+    _ensure_native_available()
+    out = ffi.new("unsigned char[]", PCR_DIGEST_LEN)
+    code = lib.nsm_extend_digest(current, data, len(data), out)
+    _raise_error(code, context="pcr")
+    return _native.buf_to_bytes(out, PCR_DIGEST_LEN)
+
+
+def validate_module_id(module_id: str) -> None:
+    """Ensure ``module_id`` is lowercase hex of at most :data:`MODULE_ID_MAX_LEN` chars."""
+
+    if not isinstance(module_id, str):
+        raise NsmModuleIdError("module ID must be a string")
+    if not 0 < len(module_id) <= MODULE_ID_MAX_LEN:
+        raise NsmModuleIdError(f"module ID must be between 1 and {MODULE_ID_MAX_LEN} characters")
+    if not _MODULE_ID_PATTERN.fullmatch(module_id):
+        raise NsmModuleIdError("module ID must contain only lowercase hex characters")
+
+
+def derive_module_id(seed: bytes) -> str:
+    """Derive a stable module ID from ``seed``.
+
+    The result is the first 16 bytes of SHA-256(``seed``) as lowercase hex, the
+    same shape as the randomly generated IDs, so it can be passed as ``module_id``.
+    """
+
+    if not isinstance(seed, (bytes, bytearray, memoryview)):
+        raise TypeError(f"seed must be bytes-like, got {type(seed).__name__}")
+    if not seed:
+        raise NsmModuleIdError("module ID seed must not be empty")
+    return hashlib.sha256(bytes(seed)).digest()[:16].hex()
+
+
+def _hkdf_expand(secret: bytes, info: bytes, length: int) -> bytes:
+    """HKDF-Expand (RFC 5869) using HMAC with :data:`DIGEST_ALGORITHM`."""
+
+    output = b""
+    block = b""
+    counter = 1
+    while len(output) < length:
+        block = hmac.new(secret, block + info + bytes([counter]), DIGEST_ALGORITHM).digest()
+        output += block
+        counter += 1
+    return output[:length]
+
+
+def build_attestation_payload(
+    pcrs: Mapping[int, bytes],
+    module_id: str,
+    *,
+    locked_pcrs: Iterable[int] = (),
+    certificate: Optional[bytes] = None,
+    cabundle: Optional[Sequence[bytes]] = None,
+    user_data: Optional[bytes] = None,
+    public_key: Optional[bytes] = None,
+    nonce: Optional[bytes] = None,
+    channel_binding: Optional[bytes] = None,
+    public_key_format: str = "raw",
+    include_pcrs: bool = True,
+    bind_module_id: bool = False,
+    bind_locks: bool = False,
+    canonical_digest: bool = False,
+    timestamp: Optional[int] = None,
+    digest: Optional[bytes] = None,
+) -> Dict[str, object]:
+    """Assemble an attestation payload purely from the supplied inputs.
+
+    PCR digests are hashed in ascending slot order; see
+    :meth:`NsmTransport._attestation_digest` for the full preimage. A supplied
+    ``digest`` is used verbatim instead of being computed. With
+    ``include_pcrs=False`` the ``pcrs`` field is ``None`` and only
+    ``pcrs_digest``, the canonical hash of the whole bank, is reported and attested.
+    """
+
+    ordered: Dict[int, bytes] = {}
+    for slot in sorted(pcrs):
+        value = bytes(pcrs[slot])
+        if slot < 0 or slot >= PCR_SLOTS:
+            raise NsmAttestationError(f"PCR slot {slot} is out of range")
+        if len(value) != PCR_DIGEST_LEN:
+            raise NsmAttestationError(
+                f"PCR slot {slot} digest must be {PCR_DIGEST_LEN} bytes, got {len(value)}"
+            )
+        ordered[slot] = value
+
+    locked = sorted(locked_pcrs)
+    pcrs_digest = None
+    if not include_pcrs:
+        pcrs_digest = NsmTransport._attestation_hasher(ordered, canonical=True).digest()
+    if digest is None:
+        digest = NsmTransport._attestation_digest(
+            ordered,
+            user_data,
+            public_key,
+            nonce,
+            module_id=module_id if bind_module_id else None,
+            locked_pcrs=locked if bind_locks else None,
+            canonical=canonical_digest,
+            channel_binding=channel_binding,
+            pcrs_digest=pcrs_digest,
+        )
+    elif len(digest) != ATTESTATION_DIGEST_LEN:
+        raise NsmAttestationError(
+            f"attestation digest must be {ATTESTATION_DIGEST_LEN} bytes, got {len(digest)}"
+        )
+    bundle = list(cabundle) if cabundle else None
+    # Presence is decided by ``is not None``, so a supplied but empty value is
+    # listed. Only channel_binding is length-prefixed in the digest; an empty
+    # user_data, public_key or nonce is listed here yet leaves the digest
+    # unchanged (see docs/usage.md).
+    optional = {
+        "user_data": user_data,
+        "public_key": public_key,
+        "nonce": nonce,
+        "certificate": certificate,
+        "cabundle": bundle,
+        "channel_binding": channel_binding,
+    }
+    return {
+        "module_id": module_id,
+        "timestamp": int(time.time()) if timestamp is None else timestamp,
+        "digest": digest,
+        "digest_algorithm": DIGEST_ALGORITHM,
+        "pcrs": ordered if include_pcrs else None,
+        "pcrs_digest": pcrs_digest,
+        "locked_pcrs": locked,
+        "certificate": certificate,
+        "cabundle": bundle,
+        "user_data": user_data,
+        "public_key": public_key,
+        "public_key_format": public_key_format if public_key else None,
+        "nonce": nonce,
+        "channel_binding": channel_binding,
+        "fields_present": [name for name, value in optional.items() if value is not None],
+    }
 
 
 def sdk_version() -> str:
@@ -301,3 +702,30 @@ def sdk_version() -> str:
 
 def default_device_path() -> str:
     return DEFAULT_DEVICE_PATH
+
+
+def is_canonical_nsm_path(path: str) -> bool:
+    """Return whether ``path`` resolves to ``/dev/nsm`` or the configured default.
+
+    Symlinks are resolved first, so a link pointing somewhere else is rejected.
+    Missing paths return ``False`` rather than raising.
+    """
+
+    if not os.path.exists(path):
+        return False
+    resolved = os.path.realpath(path)
+    return resolved in {"/dev/nsm", os.path.abspath(default_device_path())}
+
+
+def is_real_enclave() -> bool:
+    """Return whether the process appears to run inside a Nitro Enclave.
+
+    Requires ``/dev/nsm`` to be a character device registered by the ``nsm``
+    misc driver; regular files or symlinks used by the simulator do not count.
+    """
+
+    try:
+        mode = os.stat("/dev/nsm").st_mode
+    except OSError:
+        return False
+    return stat.S_ISCHR(mode) and os.path.isdir("/sys/class/misc/nsm")
@@ -2,14 +2,65 @@
 
 from __future__ import annotations
 
-from typing import Callable, Optional, cast
+import base64
+import hashlib
+import hmac
+import importlib.util
+import json
+import logging
+import os
+import time
+from collections import deque
+from typing import (
+    Any,
+    Callable,
+    Deque,
+    Dict,
+    Iterable,
+    Iterator,
+    List,
+    Mapping,
+    Optional,
+    Sequence,
+    Sized,
+    Tuple,
+    Union,
+    cast,
+)
 
-from . import _transport
-from .errors import NsmError, NsmRandomError
-from .types import AttestationDocument, PcrValue
+from . import _cbor, _msgpack, _pem, _transport
+from .errors import (
+    NsmAttestationError,
+    NsmAttestationInputError,
+    NsmCertificateError,
+    NsmCertificateTooShortError,
+    NsmError,
+    NsmExtendTooLargeError,
+    NsmInvalidPcrError,
+    NsmPcrLockedError,
+    NsmPcrReservedError,
+    NsmRandomError,
+    NsmSessionFrozenError,
+)
+from .types import AttestationDocument, Capability, PcrValue, hex_encoder
+
+_LOGGER = logging.getLogger(__name__)
 
 TransportFactory = Callable[[Optional[str]], _transport.NsmTransport]
 
+_FINGERPRINT_ALGORITHMS = ("sha256", "sha384", "sha512")
+
+# Session handoff blob: magic, format version, then the fields written by
+# NsmClient._session_blob. Bump the version whenever that layout changes.
+_SESSION_MAGIC = b"NSMS"
+_SESSION_VERSION = 1
+# Far above any real session (1 KiB of PCRs plus a few certificates); bounds
+# what a peer can make recv_from_fd read.
+_SESSION_MAX_LEN = 16 * 1024 * 1024
+
+# COSE algorithm identifiers (RFC 9053) for the supported attestation signers.
+SIGNING_ALGORITHMS = {"es384": -35, "eddsa": -8}
+
 
 class NsmClient:
     """Blocking NSM client backed by the native CFFI transport."""
@@ -19,8 +70,85 @@ class NsmClient:
         *,
         device_path: Optional[str] = None,
         transport_factory: Optional[TransportFactory] = None,
+        bind_module_id: bool = False,
+        bind_locks: bool = False,
+        leaf_cert_slot: int = 0,
+        module_id: Optional[str] = None,
+        canonical_digest: bool = False,
+        reserved_slots: Iterable[int] = range(8),
+        min_certificate_len: int = 0,
+        digest_history: int = 8,
+        production: bool = False,
+        signing_algorithm: str = "es384",
+        nonce_len_range: Tuple[int, int] = (1, 512),
+        public_key_len_range: Tuple[int, int] = (1, 4096),
+        max_extend_len: int = 1024 * 1024,
     ) -> None:
+        """Create a client; the device is opened lazily by :meth:`open`.
+
+        ``bind_module_id`` mixes the module ID into every attestation digest so
+        two modules with identical PCRs still produce distinct digests.
+        ``bind_locks`` commits the PCR lock bitmap into the digest as well.
+        ``leaf_cert_slot`` selects the certificate slot reported as the attestation
+        leaf; the remaining populated slots form the ``cabundle``.
+        ``module_id`` replaces the session's generated ID; it must be lowercase hex
+        of at most ``MODULE_ID_MAX_LEN`` characters, like the generated IDs.
+        ``canonical_digest`` hashes PCRs with an explicit slot/length prefix
+        instead of bare concatenation (see ``docs/usage.md``).
+        ``reserved_slots`` (firmware/bootloader PCRs 0-7 by default) may only be
+        extended when ``allow_reserved=True`` is passed; reading them is allowed.
+        ``min_certificate_len`` rejects stored certificates shorter than this many
+        bytes, catching truncated DER early; ``0`` disables the check.
+        ``digest_history`` is how many recent attestation digests are kept for
+        :meth:`recent_digest_collision`.
+        ``production`` declares that the caller expects a real enclave; opening a
+        simulated session then logs a warning.
+        ``signing_algorithm`` names the COSE algorithm for signed attestations:
+        ``"es384"`` (what AWS Nitro uses) or ``"eddsa"``.
+        ``nonce_len_range`` and ``public_key_len_range`` are inclusive
+        ``(min, max)`` byte lengths accepted by the attestation calls; omitted
+        values are always accepted.
+        ``max_extend_len`` caps the bytes accepted by a single extend; larger
+        inputs raise :class:`NsmExtendTooLargeError` and should be hashed first.
+        """
+
+        if not 0 <= leaf_cert_slot < _transport.CERTIFICATE_SLOTS:
+            raise NsmCertificateError(f"leaf certificate slot {leaf_cert_slot} is out of range")
+
         self._device_path = device_path
+        self._bind_module_id = bind_module_id
+        self._bind_locks = bind_locks
+        self._leaf_cert_slot = leaf_cert_slot
+        if module_id is not None:
+            _transport.validate_module_id(module_id)
+        self._module_id = module_id
+        self._canonical_digest = canonical_digest
+        self._reserved_slots = frozenset(reserved_slots)
+        for slot in self._reserved_slots:
+            if not 0 <= slot < _transport.PCR_SLOTS:
+                raise NsmInvalidPcrError(f"reserved PCR slot {slot} is out of range")
+        if min_certificate_len < 0:
+            raise NsmCertificateError("minimum certificate length must be non-negative")
+        self._min_certificate_len = min_certificate_len
+        if digest_history < 2:
+            raise NsmError("digest history must hold at least 2 digests")
+        self._recent_digests: Deque[bytes] = deque(maxlen=digest_history)
+        self._production = production
+        if signing_algorithm not in SIGNING_ALGORITHMS:
+            supported = ", ".join(SIGNING_ALGORITHMS)
+            raise NsmError(f"unsupported signing algorithm '{signing_algorithm}' (use {supported})")
+        self._signing_algorithm = signing_algorithm
+        for name, (low, high) in (
+            ("nonce", nonce_len_range),
+            ("public key", public_key_len_range),
+        ):
+            if not 0 <= low <= high:
+                raise NsmError(f"{name} length range ({low}, {high}) is invalid")
+        self._nonce_len_range = nonce_len_range
+        self._public_key_len_range = public_key_len_range
+        if max_extend_len <= 0:
+            raise NsmError("max extend length must be greater than zero")
+        self._max_extend_len = max_extend_len
         self._transport_factory = transport_factory or _transport.NsmTransport
         self._transport: Optional[_transport.NsmTransport] = None
 
@@ -44,20 +172,207 @@ class NsmClient:
     def is_open(self) -> bool:
         return self._transport is not None and not self._transport.is_closed
 
+    @property
+    def is_frozen(self) -> bool:
+        return self._transport is not None and self._transport.is_frozen
+
+    def supported_capabilities(self) -> List[Capability]:
+        """Return the capabilities available with this build and configuration.
+
+        Crypto-backed features require the optional ``cryptography`` package;
+        digest-binding features are listed only when enabled at construction.
+        """
+
+        capabilities = [Capability.CBOR_DESCRIBE, Capability.KEY_DERIVATION]
+        if importlib.util.find_spec("cryptography") is not None:
+            capabilities += [Capability.SEALING, Capability.CERTIFICATE_CHAIN_VERIFICATION]
+        if self._bind_module_id:
+            capabilities.append(Capability.MODULE_ID_BINDING)
+        if self._bind_locks:
+            capabilities.append(Capability.LOCK_BINDING)
+        if self._canonical_digest:
+            capabilities.append(Capability.CANONICAL_DIGEST)
+        return capabilities
+
     def open(self) -> None:
         if self._transport is None or self._transport.is_closed:
             self._transport = self._transport_factory(self._device_path)
+            if self._module_id is not None:
+                self._transport.set_module_id(self._module_id)
+            if self._production and self._transport.is_simulated:
+                _LOGGER.warning(
+                    "NSM session on %s is simulated but the client was created with "
+                    "production=True; attestations will not come from a real enclave",
+                    self._transport.device_path,
+                )
 
     def close(self) -> None:
         if self._transport is not None and not self._transport.is_closed:
             self._transport.close()
 
+    def freeze(self) -> None:
+        """Seal the session against any further PCR, lock or certificate changes.
+
+        Mutating calls then raise :class:`NsmSessionFrozenError`; reads and
+        attestation keep working. Freezing an already frozen session is a no-op.
+        """
+
+        transport = self._require_transport()
+        transport.freeze()
+
+    def is_simulated(self) -> bool:
+        """Return whether the session is backed by the in-memory simulator."""
+
+        transport = self._require_transport()
+        return transport.is_simulated
+
     def get_random(self, length: int) -> bytes:
         if length <= 0:
             raise NsmRandomError("length must be greater than zero")
         transport = self._require_transport()
         return transport.get_random(length)
 
+    def rng_backend(self) -> str:
+        """Return the random source in use: ``"os"`` or ``"seeded"``.
+
+        ``"seeded"`` is a deterministic PRNG suitable only for testing; production
+        readiness checks should require ``"os"``.
+        """
+
+        transport = self._require_transport()
+        return transport.rng_backend
+
+    def get_random_buffer(self, length: int) -> bytearray:
+        """Return ``length`` random bytes in a mutable ``bytearray``.
+
+        The native shim fills the bytearray in place, so unlike :meth:`get_random`
+        no intermediate ``bytes`` copy is made.
+        """
+
+        if length <= 0:
+            raise NsmRandomError("length must be greater than zero")
+        transport = self._require_transport()
+        return transport.get_random_buffer(length)
+
+    def get_random_bits(self, bits: int) -> int:
+        """Return a random non-negative integer below ``2 ** bits``."""
+
+        if bits <= 0:
+            raise NsmRandomError("bits must be greater than zero")
+        transport = self._require_transport()
+        raw = transport.get_random((bits + 7) // 8)
+        return int.from_bytes(raw, "big") & ((1 << bits) - 1)
+
+    def get_random_matrix(self, count: int, each_len: int) -> List[bytes]:
+        """Return ``count`` random byte strings of ``each_len`` bytes each.
+
+        All rows come from a single RNG draw, which avoids per-call overhead when
+        generating fixed-shape batches such as key material.
+        """
+
+        if count <= 0:
+            raise NsmRandomError("count must be greater than zero")
+        if each_len <= 0:
+            raise NsmRandomError("each_len must be greater than zero")
+        transport = self._require_transport()
+        pool = transport.get_random(count * each_len)
+        return [pool[index * each_len : (index + 1) * each_len] for index in range(count)]
+
+    def get_random_chunked(self, total: int, chunk: int) -> Iterator[bytes]:
+        """Yield ``total`` random bytes as chunks of at most ``chunk`` bytes.
+
+        Each chunk is drawn from the session RNG only when requested, so peak
+        memory stays around ``chunk`` bytes however large ``total`` is.
+        """
+
+        if total <= 0:
+            raise NsmRandomError("total must be greater than zero")
+        if chunk <= 0:
+            raise NsmRandomError("chunk must be greater than zero")
+        transport = self._require_transport()
+
+        def chunks() -> Iterator[bytes]:
+            remaining = total
+            while remaining:
+                size = min(chunk, remaining)
+                yield transport.get_random(size)
+                remaining -= size
+
+        return chunks()
+
+    def benchmark_random(self, total_bytes: int, chunk: int) -> Dict[str, float]:
+        """Time drawing ``total_bytes`` random bytes in ``chunk``-sized requests.
+
+        Returns ``bytes``, ``elapsed_ms`` and ``mb_per_sec`` (MB = 10**6 bytes);
+        the generated data is discarded.
+        """
+
+        chunks = self.get_random_chunked(total_bytes, chunk)
+        started = time.perf_counter()
+        for _ in chunks:
+            pass
+        elapsed = time.perf_counter() - started
+        return {
+            "bytes": total_bytes,
+            "elapsed_ms": elapsed * 1000,
+            "mb_per_sec": total_bytes / 1e6 / elapsed if elapsed > 0 else float("inf"),
+        }
+
+    def derive_key(self, context: bytes, length: int) -> bytes:
+        """Derive ``length`` key bytes bound to ``context``.
+
+        Uses HKDF-Expand over a secret drawn from the OS CSPRNG when the session
+        was opened. The same context yields the same key for the life of the session;
+        the secret itself is never exposed.
+        """
+
+        if not 0 < length <= _transport.MAX_DERIVED_KEY_LEN:
+            raise NsmError(
+                f"derived key length must be between 1 and {_transport.MAX_DERIVED_KEY_LEN}"
+            )
+        transport = self._require_transport()
+        return transport.derive_key(context, length)
+
+    def seal(self, plaintext: bytes, pcr_selection: Iterable[int]) -> bytes:
+        """Encrypt ``plaintext`` so it only decrypts at the current PCR state.
+
+        The AES-256-GCM key is derived from the session secret and the digests
+        of the selected PCRs. Because the secret lives only as long as the
+        session, sealed data can be unsealed by this session alone. Requires the
+        ``cryptography`` package (``verifier`` extra).
+        """
+
+        aesgcm_cls = _aesgcm()
+        selection = self._normalise_pcr_selection(pcr_selection)
+        key, context = self._sealing_key(selection)
+        # GCM must never reuse a nonce under one key; the shim RNG is clock-seeded.
+        nonce = os.urandom(12)
+        return nonce + aesgcm_cls(key).encrypt(nonce, plaintext, context)
+
+    def unseal(self, ciphertext: bytes, pcr_selection: Iterable[int]) -> bytes:
+        """Decrypt data produced by :meth:`seal` using the current PCR state.
+
+        Raises :class:`NsmError` if any selected PCR changed since sealing, the
+        selection differs, or the ciphertext was tampered with.
+        """
+
+        aesgcm_cls = _aesgcm()
+        selection = self._normalise_pcr_selection(pcr_selection)
+        if len(ciphertext) < 12:
+            raise NsmError("sealed data is truncated")
+        key, context = self._sealing_key(selection)
+        try:
+            return cast(bytes, aesgcm_cls(key).decrypt(ciphertext[:12], ciphertext[12:], context))
+        except Exception as exc:  # cryptography raises InvalidTag
+            raise NsmError(
+                "unable to unseal data: PCR state changed or data is corrupt", cause=exc
+            )
+
+    def readonly_view(self) -> "NsmReadOnlyView":
+        """Return a view exposing only the read and attestation methods of this client."""
+
+        return NsmReadOnlyView(self)
+
     def describe_pcr(self, slot: int) -> PcrValue:
         if slot < 0:
             raise NsmError("PCR slot must be non-negative")
@@ -73,29 +388,705 @@ class NsmClient:
         transport = self._require_transport()
         return transport.describe_pcr_raw(slot)
 
-    def extend_pcr(self, slot: int, data: bytes) -> PcrValue:
+    def extend_pcr(
+        self,
+        slot: int,
+        data: bytes,
+        *,
+        tag: Optional[bytes] = None,
+        allow_reserved: bool = False,
+    ) -> PcrValue:
+        """Extend ``slot`` with ``data``.
+
+        When ``tag`` is supplied it is prefixed to the data with its length, so
+        the new value is ``H(current || len(tag) (2 bytes BE) || tag || data)``.
+        This namespaces measurements coming from different event sources: a
+        tagged extend never equals an untagged one of ``tag + data``. Omitting
+        ``tag`` keeps the untagged result; tags are limited to 65535 bytes.
+        Reserved slots raise :class:`NsmPcrReservedError` unless ``allow_reserved``.
+        """
+
+        if slot < 0:
+            raise NsmError("PCR slot must be non-negative")
+        self._check_reserved(slot, allow_reserved)
+        if not data:
+            raise NsmError("data to extend must not be empty")
+        if tag is not None and not tag:
+            raise NsmError("extend tag must not be empty when provided")
+        if tag is not None and len(tag) > 0xFFFF:
+            raise NsmError("extend tag must be at most 65535 bytes")
+        self._check_extend_len(data)
+        transport = self._require_transport()
+        digest = transport.extend_pcr(slot, data, tag=tag)
+        locked = bool(transport.describe_pcr_raw(slot).get("locked", False))
+        return PcrValue(slot=slot, digest=digest, locked=locked)
+
+    def extend_pcr_object(
+        self, slot: int, obj: object, *, allow_reserved: bool = False
+    ) -> PcrValue:
+        """Extend ``slot`` with the bytes of any buffer-protocol object.
+
+        Accepts ``bytes``, ``bytearray``, ``memoryview`` and other objects exposing
+        the buffer protocol; anything else raises ``TypeError``.
+        """
+
+        try:
+            with memoryview(obj) as view:  # type: ignore[arg-type]
+                data = view.tobytes()
+        except TypeError:
+            raise TypeError(
+                f"extend_pcr_object expects a bytes-like object, got {type(obj).__name__}"
+            ) from None
+        return self.extend_pcr(slot, data, allow_reserved=allow_reserved)
+
+    def extend_pcr_many(
+        self, slot: int, chunks: Iterable[bytes], *, allow_reserved: bool = False
+    ) -> PcrValue:
+        """Extend ``slot`` with each chunk in order.
+
+        Every chunk is validated before the PCR is touched, so a malformed batch
+        leaves the slot unchanged. Errors name the offending chunk index.
+        """
+
         if slot < 0:
             raise NsmError("PCR slot must be non-negative")
+        self._check_reserved(slot, allow_reserved)
+        batch = list(chunks)
+        if not batch:
+            raise NsmError("chunks to extend must not be empty")
+        for index, chunk in enumerate(batch):
+            if not isinstance(chunk, (bytes, bytearray, memoryview)):
+                raise NsmError(f"chunk {index} must be bytes-like, got {type(chunk).__name__}")
+            if not chunk:
+                raise NsmError(f"chunk {index} must not be empty")
+            self._check_extend_len(chunk, f"chunk {index}")
+        transport = self._require_transport()
+        if transport.describe_pcr_raw(slot).get("locked", False):
+            raise NsmPcrLockedError(f"PCR slot {slot} is locked")
+        digest = b""
+        for chunk in batch:
+            digest = transport.extend_pcr(slot, bytes(chunk))
+        return PcrValue(slot=slot, digest=digest, locked=False)
+
+    def extend_pcrs(
+        self, slots: Iterable[int], data: bytes, *, allow_reserved: bool = False
+    ) -> Dict[int, bytes]:
+        """Extend every slot in ``slots`` with the same ``data``.
+
+        All slots are validated and checked for locks before any is extended, so
+        the call either updates every slot or none. Returns the new digest per slot.
+        """
+
+        targets = list(slots)
+        if not targets:
+            raise NsmError("slots to extend must not be empty")
+        if len(set(targets)) != len(targets):
+            raise NsmError("slots to extend must not contain duplicates")
         if not data:
             raise NsmError("data to extend must not be empty")
+        self._check_extend_len(data)
+        transport = self._require_transport()
+        for slot in targets:
+            if slot < 0:
+                raise NsmError("PCR slot must be non-negative")
+            self._check_reserved(slot, allow_reserved)
+            if transport.describe_pcr_raw(slot).get("locked", False):
+                raise NsmPcrLockedError(f"PCR slot {slot} is locked")
+        return {slot: transport.extend_pcr(slot, data) for slot in targets}
+
+    def extend_pcr_from_file(
+        self,
+        slot: int,
+        path: str,
+        chunk_size: int = 64 * 1024,
+        *,
+        allow_reserved: bool = False,
+    ) -> PcrValue:
+        """Extend ``slot`` once with the digest of the file at ``path``.
+
+        The file is streamed in ``chunk_size`` pieces so large images never have
+        to be held in memory. Unreadable files raise before the PCR is touched.
+        """
+
+        if slot < 0:
+            raise NsmError("PCR slot must be non-negative")
+        self._check_reserved(slot, allow_reserved)
+        if chunk_size <= 0:
+            raise NsmError("chunk size must be greater than zero")
         transport = self._require_transport()
-        digest = transport.extend_pcr(slot, data)
+        digest = transport.extend_pcr(slot, _file_digest(path, chunk_size))
         locked = bool(transport.describe_pcr_raw(slot).get("locked", False))
         return PcrValue(slot=slot, digest=digest, locked=locked)
 
-    def set_certificate(self, slot: int, certificate: bytes) -> None:
+    def extend_pcr_env(
+        self,
+        slot: int,
+        var_names: Iterable[str],
+        *,
+        allow_missing: bool = True,
+        allow_reserved: bool = False,
+    ) -> PcrValue:
+        """Extend ``slot`` with the digest of the named environment variables.
+
+        Variables are sorted by name and serialised as ``name=value\\n`` lines in
+        UTF-8, so the measurement does not depend on the order names are given.
+        Unset variables measure as empty values, or raise when ``allow_missing``
+        is false. Names containing ``=``, a newline or NUL and values containing
+        a newline are rejected, since they would make two different
+        environments serialise to the same bytes.
+        """
+
+        names = sorted(var_names)
+        if not names:
+            raise NsmError("environment variable names must not be empty")
+        if len(set(names)) != len(names):
+            raise NsmError("environment variable names must not contain duplicates")
+        for name in names:
+            if not name or any(char in name for char in "=\n\0"):
+                raise NsmError(f"environment variable name {name!r} is not measurable")
+        lines = []
+        for name in names:
+            value = os.environ.get(name)
+            if value is None and not allow_missing:
+                raise NsmError(f"environment variable '{name}' is not set")
+            if value is not None and "\n" in value:
+                raise NsmError(f"environment variable '{name}' contains a newline")
+            lines.append(f"{name}={value or ''}\n")
+        encoded = "".join(lines).encode("utf-8")
+        digest = hashlib.new(_transport.DIGEST_ALGORITHM, encoded).digest()
+        return self.extend_pcr(slot, digest, allow_reserved=allow_reserved)
+
+    def pcr_matches_file(self, slot: int, path: str) -> bool:
+        """Return whether ``slot`` holds exactly the measurement of the file at ``path``.
+
+        The expected value is a zeroed PCR extended once with the file digest,
+        as :meth:`extend_pcr_from_file` does, compared in constant time.
+        Unreadable files raise :class:`NsmError`.
+        """
+
+        if slot < 0:
+            raise NsmError("PCR slot must be non-negative")
+        transport = self._require_transport()
+        current = transport.describe_pcr(slot)
+        expected = _transport.extend_digest(bytes(_transport.PCR_DIGEST_LEN), _file_digest(path))
+        return hmac.compare_digest(current, expected)
+
+    def pcr_bank_summary(self) -> bytes:
+        """Return one digest identifying the whole PCR bank.
+
+        Every slot is hashed in order with the canonical ``slot || length ||
+        digest`` encoding used for ``canonical_digest`` attestations, so equal
+        banks always give equal summaries.
+        """
+
+        transport = self._require_transport()
+        bank = dict(enumerate(transport.describe_pcr_bank()))
+        hasher = _transport.NsmTransport._attestation_hasher(bank, canonical=True)
+        return cast(bytes, hasher.digest())
+
+    def pcr_merkle_root(self) -> bytes:
+        """Return the root of a binary Merkle tree over the 32 PCR digests.
+
+        Leaves are ``H(0x00 || digest)`` in slot order and inner nodes are
+        ``H(0x01 || left || right)``, so a leaf can never pass for a node.
+        """
+
+        transport = self._require_transport()
+        return _merkle_levels(transport.describe_pcr_bank())[-1][0]
+
+    def pcr_merkle_proof(self, slot: int) -> List[bytes]:
+        """Return the sibling hashes proving ``slot`` under :meth:`pcr_merkle_root`.
+
+        The path runs from the leaf up to the root; check it with
+        :meth:`verify_pcr_merkle_proof` without revealing the other PCRs.
+        """
+
+        if not 0 <= slot < _transport.PCR_SLOTS:
+            raise NsmInvalidPcrError(f"PCR slot {slot} is out of range")
+        transport = self._require_transport()
+        proof = []
+        index = slot
+        for level in _merkle_levels(transport.describe_pcr_bank())[:-1]:
+            proof.append(level[index ^ 1])
+            index //= 2
+        return proof
+
+    @staticmethod
+    def verify_pcr_merkle_proof(
+        root: bytes, slot: int, digest: bytes, proof: Sequence[bytes]
+    ) -> bool:
+        """Return whether ``digest`` sits at ``slot`` under the Merkle ``root``."""
+
+        if not 0 <= slot < _transport.PCR_SLOTS:
+            raise NsmInvalidPcrError(f"PCR slot {slot} is out of range")
+        node = hashlib.new(_transport.DIGEST_ALGORITHM, b"\x00" + digest).digest()
+        index = slot
+        for sibling in proof:
+            pair = sibling + node if index & 1 else node + sibling
+            node = hashlib.new(_transport.DIGEST_ALGORITHM, b"\x01" + pair).digest()
+            index //= 2
+        return hmac.compare_digest(node, root)
+
+    def evaluate_policy(
+        self, policy: Mapping[int, Union[bytes, Sequence[bytes]]]
+    ) -> Dict[str, Any]:
+        """Check PCRs against a launch policy mapping slot -> acceptable digest(s).
+
+        Each slot may list several acceptable digests to allow more than one
+        approved version. Returns ``{"allowed": bool, "slots": {slot: bool}}``;
+        every candidate is compared in constant time. An empty policy, a slot
+        with no candidates or a candidate that is not ``PCR_DIGEST_LEN`` bytes
+        raises :class:`NsmError` rather than being allowed or silently failed.
+        """
+
+        if not policy:
+            raise NsmError("policy must name at least one PCR slot")
+        normalised: Dict[int, List[bytes]] = {}
+        for slot, expected in policy.items():
+            if not 0 <= slot < _transport.PCR_SLOTS:
+                raise NsmInvalidPcrError(f"policy PCR slot {slot} is out of range")
+            candidates = [expected] if isinstance(expected, (bytes, bytearray)) else expected
+            normalised[slot] = [bytes(candidate) for candidate in candidates]
+            if not normalised[slot]:
+                raise NsmError(f"policy for PCR slot {slot} lists no acceptable digests")
+            for candidate in normalised[slot]:
+                if len(candidate) != _transport.PCR_DIGEST_LEN:
+                    raise NsmError(
+                        f"policy digest for PCR slot {slot} must be "
+                        f"{_transport.PCR_DIGEST_LEN} bytes, got {len(candidate)}"
+                    )
+        bank = self._require_transport().describe_pcr_bank()
+        results: Dict[int, bool] = {}
+        for slot, candidates in sorted(normalised.items()):
+            matched = False
+            for candidate in candidates:
+                matched |= hmac.compare_digest(bank[slot], candidate)
+            results[slot] = matched
+        return {"allowed": all(results.values()), "slots": results}
+
+    def detect_slot_swaps(self, expected: Mapping[int, bytes]) -> List[Tuple[int, int]]:
+        """Return slot pairs whose actual digests look swapped relative to ``expected``.
+
+        ``(a, b)`` is reported, with ``a < b``, when slot ``a`` holds the digest
+        expected for ``b`` and vice versa while the two expectations differ.
+        """
+
+        for slot in expected:
+            if not 0 <= slot < _transport.PCR_SLOTS:
+                raise NsmInvalidPcrError(f"PCR slot {slot} is out of range")
+        transport = self._require_transport()
+        bank = transport.describe_pcr_bank()
+        slots = sorted(expected)
+        swaps = []
+        for index, first in enumerate(slots):
+            for second in slots[index + 1 :]:
+                if expected[first] == expected[second]:
+                    continue
+                if bank[first] == expected[second] and bank[second] == expected[first]:
+                    swaps.append((first, second))
+        return swaps
+
+    def find_duplicate_pcrs(self, *, ignore_zero: bool = True) -> List[List[int]]:
+        """Return groups of PCR slots that share an identical digest.
+
+        Untouched slots all hold the zero digest, so they are skipped unless
+        ``ignore_zero`` is disabled.
+        """
+
+        transport = self._require_transport()
+        groups: Dict[bytes, List[int]] = {}
+        for slot, digest in enumerate(transport.describe_pcr_bank()):
+            if ignore_zero and not any(digest):
+                continue
+            groups.setdefault(digest, []).append(slot)
+        return [slots for slots in groups.values() if len(slots) > 1]
+
+    def is_pristine(self) -> bool:
+        """Return whether no PCR was extended or locked and no certificate is set."""
+
+        transport = self._require_transport()
+        if self.extended_slots():
+            return False
+        return not transport.locked_slots() and not transport.certificate_slots()
+
+    def extended_slots(self) -> List[int]:
+        """Return the PCR slots whose digest is no longer all zeros, in ascending order.
+
+        Locking alone does not change a digest, so locked but unextended slots
+        are not listed.
+        """
+
+        transport = self._require_transport()
+        return [slot for slot, digest in enumerate(transport.describe_pcr_bank()) if any(digest)]
+
+    def export_pcrs_tsv(self, hex_case: str = "lower") -> str:
+        """Return every PCR as a ``slot<TAB>hexdigest`` line, slot 0 first.
+
+        ``hex_case="upper"`` emits uppercase digests; :meth:`import_pcrs_tsv`
+        accepts either case.
+        """
+
+        hex_bytes = hex_encoder(hex_case)
+        transport = self._require_transport()
+        return "\n".join(
+            f"{slot}\t{hex_bytes(digest)}"
+            for slot, digest in enumerate(transport.describe_pcr_bank())
+        )
+
+    def import_pcrs_tsv(self, text: str, *, allow_reserved: bool = False) -> None:
+        """Restore PCR values from :meth:`export_pcrs_tsv` output.
+
+        Blank lines are ignored. The whole text is validated before any PCR is
+        written; malformed lines raise with their 1-based line number and locked
+        slots raise :class:`NsmPcrLockedError`. Changing a reserved slot raises
+        :class:`NsmPcrReservedError` unless ``allow_reserved``; entries equal to
+        the current value are accepted, so a full export re-imports cleanly.
+        """
+
+        transport = self._require_transport()
+        entries: List[Tuple[int, bytes]] = []
+        for number, line in enumerate(text.splitlines(), start=1):
+            if not line.strip():
+                continue
+            fields = line.strip().split("\t")
+            if len(fields) != 2:
+                raise NsmError(f"line {number}: expected 'slot<TAB>hexdigest'")
+            try:
+                slot = int(fields[0])
+                digest = bytes.fromhex(fields[1])
+            except ValueError as exc:
+                raise NsmError(f"line {number}: malformed PCR entry", cause=exc)
+            if not 0 <= slot < _transport.PCR_SLOTS:
+                raise NsmInvalidPcrError(f"line {number}: PCR slot {slot} is out of range")
+            if len(digest) != _transport.PCR_DIGEST_LEN:
+                raise NsmError(
+                    f"line {number}: digest must be {_transport.PCR_DIGEST_LEN} bytes, "
+                    f"got {len(digest)}"
+                )
+            entries.append((slot, digest))
+        locked = set(transport.locked_slots())
+        current = transport.describe_pcr_bank()
+        for slot, digest in entries:
+            if slot in locked:
+                raise NsmPcrLockedError(f"PCR slot {slot} is locked")
+            if digest != current[slot]:
+                self._check_reserved(slot, allow_reserved)
+        for slot, digest in entries:
+            transport.set_pcr(slot, digest)
+
+    def pcr_bank_bytes(self) -> bytes:
+        """Return all PCR digests concatenated in slot order.
+
+        The buffer is ``PCR_SLOTS * PCR_DIGEST_LEN`` bytes, cheaper to hash or
+        send than the per-slot accessors; :meth:`set_pcr_bank_bytes` reads it back.
+        """
+
+        transport = self._require_transport()
+        return b"".join(transport.describe_pcr_bank())
+
+    def set_pcr_bank_bytes(self, data: bytes, *, allow_reserved: bool = False) -> None:
+        """Restore every PCR from :meth:`pcr_bank_bytes` output.
+
+        The length must match exactly. Locked slots must keep their current
+        value, otherwise :class:`NsmPcrLockedError` is raised, and reserved slots
+        may only change with ``allow_reserved`` (:class:`NsmPcrReservedError`);
+        both checks run before any PCR is written.
+        """
+
+        expected = _transport.PCR_SLOTS * _transport.PCR_DIGEST_LEN
+        if len(data) != expected:
+            raise NsmError(f"PCR bank must be {expected} bytes, got {len(data)}")
+        transport = self._require_transport()
+        size = _transport.PCR_DIGEST_LEN
+        bank = [bytes(data[start : start + size]) for start in range(0, expected, size)]
+        current = transport.describe_pcr_bank()
+        locked = set(transport.locked_slots())
+        for slot in range(_transport.PCR_SLOTS):
+            if bank[slot] != current[slot]:
+                if slot in locked:
+                    raise NsmPcrLockedError(f"PCR slot {slot} is locked")
+                self._check_reserved(slot, allow_reserved)
+        for slot, digest in enumerate(bank):
+            if slot not in locked and digest != current[slot]:
+                transport.set_pcr(slot, digest)
+
+    def send_to_fd(self, fd: int) -> None:
+        """Write the session state to file descriptor ``fd``, e.g. a pipe.
+
+        The state (module ID, PCRs, locks, certificates and labels) is sent as a
+        length-prefixed, versioned blob that :meth:`recv_from_fd` reads back.
+        The blob is built and validated in full before anything is written, so
+        an unsendable state (e.g. a label over 65535 bytes) raises
+        :class:`NsmError` without truncating the stream. Short writes are retried
+        until the whole blob is written.
+        """
+
+        blob = self._session_blob()
+        view = memoryview(len(blob).to_bytes(4, "big") + blob)
+        while view:
+            view = view[os.write(fd, view) :]
+
+    @staticmethod
+    def recv_from_fd(fd: int, **client_options: Any) -> "NsmClient":
+        """Read a blob written by :meth:`send_to_fd` and return an open client with that state.
+
+        ``client_options`` are passed to :class:`NsmClient`, except ``module_id``:
+        the module ID always comes from the blob, so passing one raises
+        :class:`NsmError`. A blob from a different format version, a truncated or
+        oversized blob, or one with trailing bytes raises :class:`NsmError`
+        before any client is created.
+        """
+
+        if "module_id" in client_options:
+            raise NsmError("recv_from_fd restores the module ID from the blob; do not pass one")
+        blob_len = int.from_bytes(_read_exact(fd, 4), "big")
+        if blob_len > _SESSION_MAX_LEN:
+            raise NsmError(
+                f"session blob of {blob_len} bytes exceeds the {_SESSION_MAX_LEN}-byte limit"
+            )
+        module_id, bank, bitmap, certificates = _parse_session_blob(_read_exact(fd, blob_len))
+
+        client = NsmClient(module_id=module_id, **client_options)
+        client.open()
+        transport = client._require_transport()
+        try:
+            for slot in range(_transport.PCR_SLOTS):
+                start = slot * _transport.PCR_DIGEST_LEN
+                transport.set_pcr(slot, bank[start : start + _transport.PCR_DIGEST_LEN])
+            for slot, certificate, label in certificates:
+                transport.set_certificate(slot, certificate, label=label)
+            for slot in range(_transport.PCR_SLOTS):
+                if bitmap[slot // 8] & (1 << (slot % 8)):
+                    transport.lock_pcr(slot)
+        except Exception:
+            client.close()
+            raise
+        return client
+
+    def to_fixture_code(self) -> str:
+        """Return Python source that rebuilds the current session state.
+
+        The module defines ``MODULE_ID`` and ``apply_fixture(client)``, which
+        restores the PCR bank, certificates (with labels) and PCR locks, in that
+        order, and applying it again to the same client is a no-op. Pass
+        ``module_id=MODULE_ID`` when creating the client to reproduce the module
+        ID as well. Output is deterministic for a given state.
+        """
+
+        transport = self._require_transport()
+        body: List[str] = []
+        bank = transport.describe_pcr_bank()
+        if any(any(digest) for digest in bank):
+            # The whole bank goes through set_pcr_bank_bytes, which leaves slots
+            # already locked at the same value alone, so the fixture can be re-applied.
+            # Restoring a snapshot is an explicit opt-in to writing reserved slots.
+            body.append("    client.set_pcr_bank_bytes(")
+            body.append("        bytes.fromhex(")
+            body.append('            "".join(')
+            body.append("                [")
+            body.extend(f'                    "{digest.hex()}",' for digest in bank)
+            body.append("                ]")
+            body.append("            )")
+            body.append("        ),")
+            body.append("        allow_reserved=True,")
+            body.append("    )")
+        for slot, certificate in transport.describe_certificates().items():
+            label = transport.certificate_label(slot)
+            suffix = f", label={label!r}" if label is not None else ""
+            body.append(
+                f'    client.set_certificate({slot}, bytes.fromhex("{certificate.hex()}"){suffix})'
+            )
+        body.extend(f"    client.lock_pcr({slot})" for slot in transport.locked_slots())
+        lines = [
+            '"""Session fixture generated by NsmClient.to_fixture_code()."""',
+            "",
+            f'MODULE_ID = "{transport.describe_nsm()["module_id"]}"',
+            "",
+            "",
+            "def apply_fixture(client):",
+            *(body or ["    pass"]),
+            "",
+        ]
+        return "\n".join(lines)
+
+    def set_certificate(
+        self, slot: int, certificate: bytes, *, label: Optional[str] = None
+    ) -> None:
+        """Store ``certificate`` in ``slot``, optionally tagged with a ``label``.
+
+        Overwriting a slot replaces its label; omitting ``label`` clears it.
+        """
+
         if slot < 0:
             raise NsmError("certificate slot must be non-negative")
         if not certificate:
-            raise NsmError("certificate payload must not be empty")
+            raise NsmCertificateError(f"certificate payload for slot {slot} must not be empty")
+        if label is not None and not label:
+            raise NsmCertificateError("certificate label must not be empty when provided")
+        self._check_certificate_len(slot, certificate)
+        transport = self._require_transport()
+        transport.set_certificate(slot, certificate, label=label)
+
+    def replace_all_certificates(self, certificates: Mapping[int, bytes]) -> None:
+        """Replace every stored certificate with ``certificates`` (slot -> DER).
+
+        The new set is validated in full first. Slots not in the mapping end up
+        empty and all labels are cleared. The swap runs under the session's
+        certificate lock, which readers and attestation also take, so they see
+        either the old chain or the new one; if installing fails part-way the
+        previous certificates are restored. The shim zeroes certificate bytes before freeing them.
+        """
+
+        replacement = {slot: bytes(data) for slot, data in certificates.items()}
+        for slot, certificate in replacement.items():
+            if not 0 <= slot < _transport.CERTIFICATE_SLOTS:
+                raise NsmCertificateError(f"certificate slot {slot} is out of range")
+            if not certificate:
+                raise NsmCertificateError(f"certificate payload for slot {slot} must not be empty")
+            self._check_certificate_len(slot, certificate)
         transport = self._require_transport()
-        transport.set_certificate(slot, certificate)
+        if transport.is_frozen:
+            raise NsmSessionFrozenError("NSM session is frozen")
+        transport.replace_certificates({slot: (data, None) for slot, data in replacement.items()})
+
+    def load_certificate_chain(self, path: str) -> List[int]:
+        """Store every certificate from a PEM bundle, leaf first.
+
+        Certificates are converted to DER and written to consecutive slots
+        starting at the configured leaf slot. The whole bundle is validated
+        before any slot is written. Returns the slots that were populated.
+        """
+
+        transport = self._require_transport()
+        try:
+            with open(path, "rb") as handle:
+                bundle = handle.read()
+        except OSError as exc:
+            raise NsmCertificateError(f"unable to read certificate bundle '{path}'", cause=exc)
+        certificates = _pem.decode_certificates(bundle)
+        if not certificates:
+            raise NsmCertificateError(f"no PEM certificates found in '{path}'")
+        available = _transport.CERTIFICATE_SLOTS - self._leaf_cert_slot
+        if len(certificates) > available:
+            raise NsmCertificateError(
+                f"certificate bundle holds {len(certificates)} certificates but only "
+                f"{available} slots are available from slot {self._leaf_cert_slot}"
+            )
+        for index, certificate in enumerate(certificates):
+            self._check_certificate_len(self._leaf_cert_slot + index, certificate)
+        slots = list(range(self._leaf_cert_slot, self._leaf_cert_slot + len(certificates)))
+        for slot, certificate in zip(slots, certificates):
+            transport.set_certificate(slot, certificate)
+        return slots
+
+    def describe_certificate(self, slot: int, format: str = "der") -> bytes:
+        """Return the certificate in ``slot`` as DER or, with ``format="pem"``, PEM."""
 
-    def describe_certificate(self, slot: int) -> bytes:
         if slot < 0:
             raise NsmError("certificate slot must be non-negative")
+        if format not in ("der", "pem"):
+            raise NsmError(f"unsupported certificate format '{format}' (use der, pem)")
         transport = self._require_transport()
-        return transport.describe_certificate(slot)
+        certificate = transport.describe_certificate(slot)
+        return _pem.encode_certificate(certificate) if format == "pem" else certificate
+
+    def certificate_label(self, slot: int) -> Optional[str]:
+        """Return the label stored with ``slot``'s certificate, if any."""
+
+        if slot < 0:
+            raise NsmError("certificate slot must be non-negative")
+        transport = self._require_transport()
+        return transport.certificate_label(slot)
+
+    def list_certificate_slots(self) -> Dict[int, Optional[str]]:
+        """Return the occupied certificate slots mapped to their labels."""
+
+        transport = self._require_transport()
+        return {slot: transport.certificate_label(slot) for slot in transport.certificate_slots()}
+
+    def certificates_present(self, slots: Iterable[int]) -> Dict[int, bool]:
+        """Return whether each of ``slots`` holds a certificate.
+
+        Every slot is range-checked before any is queried.
+        """
+
+        targets = list(slots)
+        for slot in targets:
+            if not 0 <= slot < _transport.CERTIFICATE_SLOTS:
+                raise NsmCertificateError(f"certificate slot {slot} is out of range")
+        transport = self._require_transport()
+        occupied = set(transport.certificate_slots())
+        return {slot: slot in occupied for slot in targets}
+
+    def certificate_fingerprints(self) -> Dict[int, bytes]:
+        """Return the SHA-256 fingerprint of every stored certificate, keyed by slot.
+
+        Empty slots are omitted.
+        """
+
+        transport = self._require_transport()
+        return {
+            slot: hashlib.sha256(certificate).digest()
+            for slot, certificate in transport.describe_certificates().items()
+        }
+
+    def certificate_fingerprints_with(self, algorithm: str) -> Dict[int, bytes]:
+        """Like :meth:`certificate_fingerprints` but hashed with ``algorithm``.
+
+        ``algorithm`` is one of ``"sha256"``, ``"sha384"`` or ``"sha512"``.
+        """
+
+        if algorithm not in _FINGERPRINT_ALGORITHMS:
+            supported = ", ".join(_FINGERPRINT_ALGORITHMS)
+            raise NsmError(f"unsupported fingerprint algorithm '{algorithm}' (use {supported})")
+        transport = self._require_transport()
+        return {
+            slot: hashlib.new(algorithm, certificate).digest()
+            for slot, certificate in transport.describe_certificates().items()
+        }
+
+    def verify_certificate_chain(self, trusted_root: Optional[bytes] = None) -> bool:
+        """Check that the stored certificates form a valid leaf-to-root chain.
+
+        The chain starts at the configured leaf slot and continues through the
+        other occupied slots in ascending order, as in the attestation
+        ``cabundle``; each certificate must be signed by the next. With
+        ``trusted_root`` (DER) the last certificate must also be issued by that
+        root; without one, the last stored certificate must be a self-signed root,
+        so an unanchored chain never verifies. Returns ``False`` on a
+        broken link or an empty leaf slot; undecodable certificates raise
+        :class:`NsmCertificateError`. Requires the ``cryptography`` package.
+        """
+
+        try:
+            from cryptography.exceptions import InvalidSignature
+        except ImportError as exc:  # pragma: no cover - optional dependency
+            raise NsmError(
+                "certificate verification requires the 'cryptography' package; "
+                "install the 'verifier' extra",
+                cause=exc,
+            )
+
+        transport = self._require_transport()
+        certificates = transport.describe_certificates()
+        leaf = certificates.pop(self._leaf_cert_slot, None)
+        if leaf is None:
+            return False
+        chain = [_load_der_certificate(leaf, f"slot {self._leaf_cert_slot}")]
+        chain.extend(
+            _load_der_certificate(data, f"slot {slot}") for slot, data in certificates.items()
+        )
+        if trusted_root is not None:
+            chain.append(_load_der_certificate(trusted_root, "trusted root"))
+        else:
+            # Without a trusted root the chain must end in a self-signed root.
+            chain.append(chain[-1])
+        for subject, issuer in zip(chain, chain[1:]):
+            try:
+                subject.verify_directly_issued_by(issuer)
+            except (InvalidSignature, ValueError):  # bad signature or issuer-name mismatch
+                return False
+        return True
 
     def remove_certificate(self, slot: int) -> None:
         if slot < 0:
@@ -109,24 +1100,134 @@ class NsmClient:
         transport = self._require_transport()
         return transport.lock_pcr(slot)
 
-    def lock_pcrs(self, lock_range: int) -> bool:
+    def lock_pcrs(self, lock_range: int, *, strict: bool = False) -> bool:
+        """Lock PCR slots ``0..lock_range``.
+
+        Ranges beyond the PCR bank are clamped to every slot unless ``strict`` is
+        set, in which case they raise :class:`NsmInvalidPcrError`.
+        """
+
         if lock_range < 0:
             raise NsmError("lock range must be non-negative")
+        if strict and lock_range > _transport.PCR_SLOTS:
+            raise NsmInvalidPcrError(
+                f"lock range {lock_range} exceeds the {_transport.PCR_SLOTS} available PCR slots"
+            )
         transport = self._require_transport()
         return transport.lock_pcrs(lock_range)
 
+    def export_locks(self) -> List[bool]:
+        """Return the lock state of every PCR slot, slot 0 first."""
+
+        transport = self._require_transport()
+        locked = set(transport.locked_slots())
+        return [slot in locked for slot in range(_transport.PCR_SLOTS)]
+
+    def import_locks(self, locks: Sequence[bool]) -> None:
+        """Restore lock state captured by :meth:`export_locks`.
+
+        ``locks`` must have one entry per PCR slot. PCR locks cannot be released,
+        so clearing a currently locked slot raises :class:`NsmPcrLockedError`;
+        nothing is locked unless the whole vector is valid.
+        """
+
+        if len(locks) != _transport.PCR_SLOTS:
+            raise NsmInvalidPcrError(
+                f"lock vector must have {_transport.PCR_SLOTS} entries, got {len(locks)}"
+            )
+        transport = self._require_transport()
+        current = set(transport.locked_slots())
+        for slot in sorted(current):
+            if not locks[slot]:
+                raise NsmPcrLockedError(f"PCR slot {slot} is locked and cannot be unlocked")
+        for slot, locked in enumerate(locks):
+            if locked and slot not in current:
+                transport.lock_pcr(slot)
+
+    def iter_locked_pcrs(self) -> Iterator[int]:
+        """Iterate over the indices of locked PCR slots in ascending order."""
+
+        transport = self._require_transport()
+        return iter(transport.locked_slots())
+
+    def seconds_since_last_change(self) -> int:
+        """Return whole seconds since the last PCR, lock or certificate mutation.
+
+        Counts from when the session was opened if nothing has changed since.
+        """
+
+        transport = self._require_transport()
+        return transport.seconds_since_last_change()
+
+    def can_attest(self, *, require_leaf_cert: bool = False) -> bool:
+        """Return whether :meth:`get_attestation` would currently succeed.
+
+        With ``require_leaf_cert`` the configured leaf certificate slot must also
+        be populated. Nothing is generated, so this is cheap enough for health checks.
+        """
+
+        if not self.is_open:
+            return False
+        if not require_leaf_cert:
+            return True
+        transport = self._require_transport()
+        return self._leaf_cert_slot in transport.certificate_slots()
+
+    def attestation_count(self) -> int:
+        """Return how many attestations this session has produced."""
+
+        transport = self._require_transport()
+        return transport.attestation_count
+
+    def recent_digest_collision(self) -> bool:
+        """Return whether the last ``digest_history`` attestation digests are identical.
+
+        ``False`` until the history is full. A ``True`` result suggests the
+        measured state is not advancing between attestations.
+        """
+
+        history = self._recent_digests
+        return len(history) == history.maxlen and len(set(history)) == 1
+
     def get_attestation(
         self,
         *,
         user_data: Optional[bytes] = None,
         public_key: Optional[bytes] = None,
         nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+        include_certificates: bool = True,
+        cabundle_order: str = "leaf_first",
+        channel_binding: Optional[bytes] = None,
+        public_key_format: str = "raw",
+        include_pcrs: bool = True,
     ) -> AttestationDocument:
-        transport = self._require_transport()
-        payload = transport.get_attestation(
+        """Create an attestation document for the current session state.
+
+        ``timestamp`` is used verbatim instead of the system clock when given,
+        which makes the output reproducible for golden-file tests. With
+        ``include_certificates=False`` the ``certificate`` and ``cabundle`` fields
+        are left empty regardless of the stored certificates. ``cabundle_order``
+        is ``"leaf_first"`` (ascending slot order) or ``"root_first"``.
+        ``channel_binding`` (e.g. a TLS exporter value) is mixed into the digest
+        and reported in the document, tying it to one connection.
+        ``public_key_format`` declares the encoding of ``public_key``: ``"raw"``,
+        ``"spki_der"`` or ``"pem"``. The key is checked for that shape and the
+        format is reported in the document. With ``include_pcrs=False`` the
+        individual PCRs are withheld and only their combined ``pcrs_digest`` is
+        reported and attested, so verifiers cannot inspect single PCRs.
+        """
+
+        payload = self.get_attestation_raw(
             user_data=user_data,
             public_key=public_key,
             nonce=nonce,
+            timestamp=timestamp,
+            include_certificates=include_certificates,
+            cabundle_order=cabundle_order,
+            channel_binding=channel_binding,
+            public_key_format=public_key_format,
+            include_pcrs=include_pcrs,
         )
         return AttestationDocument.from_payload(payload)
 
@@ -136,17 +1237,605 @@ class NsmClient:
         user_data: Optional[bytes] = None,
         public_key: Optional[bytes] = None,
         nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+        include_certificates: bool = True,
+        cabundle_order: str = "leaf_first",
+        channel_binding: Optional[bytes] = None,
+        public_key_format: str = "raw",
+        include_pcrs: bool = True,
     ) -> dict:
+        return self._build_attestation(
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+            timestamp=timestamp,
+            include_certificates=include_certificates,
+            cabundle_order=cabundle_order,
+            channel_binding=channel_binding,
+            public_key_format=public_key_format,
+            include_pcrs=include_pcrs,
+        )
+
+    def _build_attestation(
+        self,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+        include_certificates: bool = True,
+        cabundle_order: str = "leaf_first",
+        channel_binding: Optional[bytes] = None,
+        public_key_format: str = "raw",
+        include_pcrs: bool = True,
+        digest: Optional[bytes] = None,
+        record: bool = True,
+    ) -> dict:
+        # Every attestation entry point comes through here so inputs are checked
+        # the same way. With record=False the payload leaves no trace in
+        # attestation_count or the recent-digest history; _record_attestation
+        # adds it later. A supplied digest is used verbatim (length-checked).
+        if timestamp is not None and timestamp < 0:
+            raise NsmError("timestamp must be non-negative")
+        self._check_attestation_inputs(public_key, nonce)
+        _check_public_key_format(public_key, public_key_format)
+        if cabundle_order not in ("leaf_first", "root_first"):
+            raise NsmError(
+                f"unsupported cabundle order '{cabundle_order}' (use leaf_first, root_first)"
+            )
+        transport = self._require_transport()
+        payload = transport.get_attestation_raw(
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+            timestamp=timestamp,
+            include_certificates=include_certificates,
+            channel_binding=channel_binding,
+            public_key_format=public_key_format,
+            include_pcrs=include_pcrs,
+            digest=digest,
+            record=False,
+            **self._attestation_options(),
+        )
+        if cabundle_order == "root_first" and payload["cabundle"]:
+            payload["cabundle"] = cast(List[bytes], payload["cabundle"])[::-1]
+        if record:
+            self._record_attestation(payload)
+        return payload
+
+    def _record_attestation(self, payload: Mapping[str, object]) -> None:
+        self._require_transport().record_attestation()
+        self._recent_digests.append(cast(bytes, payload["digest"]))
+
+    def self_attest_roundtrip(self) -> bool:
+        """Produce a signed attestation and verify it with the session public key.
+
+        A random nonce is attested, the document is signed with
+        :meth:`sign_attestation` and the signature is checked with
+        :meth:`verify_attestation_signature`, exercising the whole signing path.
+        Returns ``True``; any failure raises :class:`NsmAttestationError`.
+        Requires the ``cryptography`` package.
+        """
+
+        nonce = self.get_random(16)
+        doc = self.get_attestation(nonce=nonce, include_certificates=False)
+        if doc.nonce != nonce:
+            raise NsmAttestationError("self-attestation did not echo the nonce")
+        signature, public_key = self.sign_attestation(doc)
+        if not NsmClient.verify_attestation_signature(doc, signature, public_key):
+            raise NsmAttestationError("self-attestation signature does not verify")
+        return True
+
+    def sign_attestation(self, doc: AttestationDocument) -> Tuple[bytes, bytes]:
+        """Sign ``doc`` with the session attestation key.
+
+        The key is derived from the session secret for the configured
+        ``signing_algorithm`` (a P-384 key for ``"es384"``, Ed25519 for
+        ``"eddsa"``) and signs the deterministic CBOR of :meth:`AttestationDocument.to_dict`.
+        Returns ``(signature, public_key)`` with the public key as DER
+        SubjectPublicKeyInfo. Requires the ``cryptography`` package.
+        """
+
+        key = self._attestation_signing_key()
+        from cryptography.hazmat.primitives import hashes, serialization
+        from cryptography.hazmat.primitives.asymmetric import ec
+
+        message = _cbor.dumps(doc.to_dict())
+        if isinstance(key, ec.EllipticCurvePrivateKey):
+            signature = key.sign(message, ec.ECDSA(hashes.SHA384()))
+        else:
+            signature = key.sign(message)
+        public_key = key.public_key().public_bytes(
+            serialization.Encoding.DER, serialization.PublicFormat.SubjectPublicKeyInfo
+        )
+        return signature, public_key
+
+    @staticmethod
+    def verify_attestation_signature(
+        doc: AttestationDocument, signature: bytes, public_key: bytes
+    ) -> bool:
+        """Return whether ``signature`` from :meth:`sign_attestation` covers ``doc``.
+
+        ``public_key`` is DER SubjectPublicKeyInfo; a key that cannot be loaded
+        or is of an unsupported type verifies nothing and gives ``False``.
+        """
+
+        try:
+            from cryptography.exceptions import InvalidSignature
+            from cryptography.hazmat.primitives import hashes, serialization
+            from cryptography.hazmat.primitives.asymmetric import ec, ed25519
+        except ImportError as exc:  # pragma: no cover - optional dependency
+            raise NsmError(
+                "signature verification requires the 'cryptography' package; "
+                "install the 'verifier' extra",
+                cause=exc,
+            )
+
+        try:
+            key = serialization.load_der_public_key(public_key)
+        except ValueError:
+            return False
+        message = _cbor.dumps(doc.to_dict())
+        try:
+            if isinstance(key, ec.EllipticCurvePublicKey):
+                key.verify(signature, message, ec.ECDSA(hashes.SHA384()))
+            elif isinstance(key, ed25519.Ed25519PublicKey):
+                key.verify(signature, message)
+            else:
+                return False
+        except InvalidSignature:
+            return False
+        return True
+
+    def attestation_digest_init(self) -> "AttestationDigestState":
+        """Start an incremental attestation digest over the current session state.
+
+        PCRs, and the module ID and locks when bound, are captured now. Feed
+        ``user_data`` with :meth:`attestation_digest_update` and finish with
+        :meth:`attestation_digest_finalize`; the result equals the digest
+        :meth:`get_attestation` would compute for the concatenated chunks and can
+        be attested with :meth:`get_attestation_with_digest`.
+        """
+
         transport = self._require_transport()
-        return transport.get_attestation_raw(
+        hasher = _transport.NsmTransport._attestation_hasher(
+            dict(enumerate(transport.describe_pcr_bank())),
+            module_id=(
+                str(transport.describe_nsm()["module_id"]) if self._bind_module_id else None
+            ),
+            locked_pcrs=transport.locked_slots() if self._bind_locks else None,
+            canonical=self._canonical_digest,
+        )
+        return AttestationDigestState(hasher)
+
+    @staticmethod
+    def attestation_digest_update(state: "AttestationDigestState", data: bytes) -> None:
+        """Feed the next chunk of ``user_data`` into ``state``."""
+
+        state._require_active().update(data)
+
+    @staticmethod
+    def attestation_digest_finalize(
+        state: "AttestationDigestState",
+        *,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        channel_binding: Optional[bytes] = None,
+    ) -> bytes:
+        """Append the remaining inputs and return the digest; ``state`` is then spent."""
+
+        hasher = state._require_active()
+        state._hasher = None
+        return _transport.NsmTransport._finish_attestation_digest(
+            hasher, public_key, nonce, channel_binding=channel_binding
+        )
+
+    def get_attestation_with_digest(
+        self,
+        digest: bytes,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        cabundle_order: str = "leaf_first",
+        public_key_format: str = "raw",
+    ) -> AttestationDocument:
+        """Create an attestation whose ``digest`` was computed elsewhere.
+
+        The digest is not recomputed, only length-checked; PCRs, locks and
+        certificates are still taken from the session. Inputs are validated as
+        in :meth:`get_attestation`.
+        """
+
+        payload = self._build_attestation(
             user_data=user_data,
             public_key=public_key,
             nonce=nonce,
+            cabundle_order=cabundle_order,
+            public_key_format=public_key_format,
+            digest=digest,
         )
+        return AttestationDocument.from_payload(payload)
+
+    def get_attestation_aws_json(
+        self,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+        hex_case: str = "lower",
+    ) -> str:
+        """Return an attestation as JSON matching AWS's document field layout.
+
+        See :meth:`AttestationDocument.to_aws_json` for the exact format.
+        """
+
+        hex_encoder(hex_case)
+        doc = self.get_attestation(
+            user_data=user_data, public_key=public_key, nonce=nonce, timestamp=timestamp
+        )
+        return doc.to_aws_json(hex_case)
+
+    def attestation_size(
+        self,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        include_certificates: bool = True,
+    ) -> int:
+        """Return the byte length of the CBOR-encoded attestation payload.
+
+        The payload is built with the given inputs but not recorded: it does not
+        count towards :meth:`attestation_count` or :meth:`recent_digest_collision`.
+        """
+
+        payload = self._build_attestation(
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+            include_certificates=include_certificates,
+            record=False,
+        )
+        return len(_cbor.dumps(payload))
+
+    def get_attestation_into(
+        self,
+        buffer: Union[bytearray, memoryview],
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        include_certificates: bool = True,
+    ) -> int:
+        """Write the CBOR-encoded attestation payload into ``buffer``.
+
+        Returns the number of bytes written. The payload is encoded to a
+        temporary ``bytes`` object and then copied into ``buffer``, so this saves
+        the caller's allocation, not the encoder's. A buffer that is too small
+        raises :class:`NsmAttestationError` naming the required size and is not
+        counted as an attestation; use :meth:`attestation_size` to presize it.
+        """
+
+        view = memoryview(buffer)
+        if view.readonly:
+            raise NsmError("attestation buffer must be writable")
+        payload = self._build_attestation(
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+            include_certificates=include_certificates,
+            record=False,
+        )
+        encoded = _cbor.dumps(payload)
+        if len(encoded) > view.nbytes:
+            raise NsmAttestationError(
+                f"attestation needs {len(encoded)} bytes but the buffer holds {view.nbytes}"
+            )
+        view.cast("B")[: len(encoded)] = encoded
+        self._record_attestation(payload)
+        return len(encoded)
+
+    def get_attestation_msgpack(
+        self,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+        include_certificates: bool = True,
+    ) -> bytes:
+        """Return the :meth:`get_attestation_raw` payload encoded as MessagePack.
+
+        Byte fields are encoded as MessagePack ``bin`` values.
+        """
+
+        payload = self.get_attestation_raw(
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+            timestamp=timestamp,
+            include_certificates=include_certificates,
+        )
+        return _msgpack.dumps(payload)
+
+    def get_attestation_jwt(
+        self,
+        signing_key: bytes,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+        timestamp: Optional[int] = None,
+    ) -> str:
+        """Return the :meth:`get_attestation_raw` payload as a compact signed JWT.
+
+        ``signing_key`` is a PKCS#8 private key (DER or PEM) matching the
+        client's ``signing_algorithm``: a P-384 key for ``ES384`` or an Ed25519 /
+        Ed448 key for ``EdDSA``. Byte fields are base64url strings without
+        padding and PCR slots become string keys. Requires ``cryptography``.
+        """
+
+        try:
+            from cryptography.hazmat.primitives import hashes, serialization
+            from cryptography.hazmat.primitives.asymmetric import ec, ed448, ed25519, utils
+        except ImportError as exc:  # pragma: no cover - optional dependency
+            raise NsmError(
+                "JWT signing requires the 'cryptography' package; install the 'verifier' extra",
+                cause=exc,
+            )
+
+        try:
+            if signing_key.lstrip().startswith(b"-----"):
+                key = serialization.load_pem_private_key(signing_key, password=None)
+            else:
+                key = serialization.load_der_private_key(signing_key, password=None)
+        except (TypeError, ValueError) as exc:
+            raise NsmError("unable to load the JWT signing key", cause=exc)
+
+        if self._signing_algorithm == "es384":
+            if not isinstance(key, ec.EllipticCurvePrivateKey) or key.curve.name != "secp384r1":
+                raise NsmError("signing algorithm 'es384' needs a P-384 private key")
+            header = {"alg": "ES384", "typ": "JWT"}
+        else:
+            if not isinstance(key, (ed25519.Ed25519PrivateKey, ed448.Ed448PrivateKey)):
+                raise NsmError("signing algorithm 'eddsa' needs an Ed25519 or Ed448 private key")
+            header = {"alg": "EdDSA", "typ": "JWT"}
+
+        payload = self.get_attestation_raw(
+            user_data=user_data, public_key=public_key, nonce=nonce, timestamp=timestamp
+        )
+        signing_input = ".".join(
+            _b64url(json.dumps(_jwt_value(part), separators=(",", ":")).encode("utf-8"))
+            for part in (header, payload)
+        ).encode("ascii")
+        if isinstance(key, ec.EllipticCurvePrivateKey):
+            # JWS wants the raw r || s pair rather than the DER-encoded signature.
+            r, s = utils.decode_dss_signature(key.sign(signing_input, ec.ECDSA(hashes.SHA384())))
+            signature = r.to_bytes(48, "big") + s.to_bytes(48, "big")
+        else:
+            signature = key.sign(signing_input)
+        return f"{signing_input.decode('ascii')}.{_b64url(signature)}"
 
     def describe_nsm(self) -> dict:
         transport = self._require_transport()
-        return transport.describe_nsm()
+        description = transport.describe_nsm()
+        description["signing_algorithm"] = self._signing_algorithm
+        return description
+
+    def describe_nsm_cbor(self) -> bytes:
+        """Return :meth:`describe_nsm` encoded as deterministic CBOR."""
+
+        return _cbor.dumps(self.describe_nsm())
+
+    @staticmethod
+    def generate_self_signed_cert(public_key: bytes, private_key: bytes, subject: str) -> bytes:
+        """Return a minimal self-signed X.509 certificate (DER) for a keypair.
+
+        Intended for tests that need a realistic leaf certificate in a slot; it
+        is not suitable for production. Keys may be DER or PEM (SPKI public key,
+        PKCS#8 private key) and must belong together. Requires ``cryptography``.
+        """
+
+        try:
+            import datetime
+
+            from cryptography import x509
+            from cryptography.hazmat.primitives import hashes, serialization
+            from cryptography.hazmat.primitives.asymmetric import ed448, ed25519
+            from cryptography.x509.oid import NameOID
+        except ImportError as exc:  # pragma: no cover - optional dependency
+            raise NsmError(
+                "certificate generation requires the 'cryptography' package; "
+                "install the 'verifier' extra",
+                cause=exc,
+            )
+
+        try:
+            if private_key.lstrip().startswith(b"-----"):
+                signing_key = serialization.load_pem_private_key(private_key, password=None)
+            else:
+                signing_key = serialization.load_der_private_key(private_key, password=None)
+            if public_key.lstrip().startswith(b"-----"):
+                verifying_key = serialization.load_pem_public_key(public_key)
+            else:
+                verifying_key = serialization.load_der_public_key(public_key)
+        except (TypeError, ValueError) as exc:
+            raise NsmCertificateError("unable to load the supplied keypair", cause=exc)
+
+        spki = serialization.Encoding.DER, serialization.PublicFormat.SubjectPublicKeyInfo
+        if signing_key.public_key().public_bytes(*spki) != verifying_key.public_bytes(*spki):
+            raise NsmCertificateError("public key does not match the private key")
+
+        name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, subject)])
+        now = datetime.datetime.now(datetime.timezone.utc)
+        builder = (
+            x509.CertificateBuilder()
+            .subject_name(name)
+            .issuer_name(name)
+            .public_key(verifying_key)  # type: ignore[arg-type]
+            .serial_number(x509.random_serial_number())
+            .not_valid_before(now - datetime.timedelta(minutes=5))
+            .not_valid_after(now + datetime.timedelta(days=1))
+        )
+        edwards = (ed25519.Ed25519PrivateKey, ed448.Ed448PrivateKey)
+        algorithm = None if isinstance(signing_key, edwards) else hashes.SHA256()
+        certificate = builder.sign(signing_key, algorithm)  # type: ignore[arg-type]
+        return certificate.public_bytes(serialization.Encoding.DER)
+
+    @staticmethod
+    def commit_user_data(data: bytes) -> bytes:
+        """Return the digest of ``data`` for use as a compact ``user_data`` value.
+
+        Attest the commitment instead of large payloads and check it later with
+        :meth:`verify_user_data_commitment`.
+        """
+
+        return hashlib.new(_transport.DIGEST_ALGORITHM, data).digest()
+
+    @staticmethod
+    def verify_user_data_commitment(doc: AttestationDocument, original: bytes) -> bool:
+        """Return whether ``doc.user_data`` is the commitment of ``original``."""
+
+        if doc.user_data is None:
+            return False
+        return hmac.compare_digest(doc.user_data, NsmClient.commit_user_data(original))
+
+    @staticmethod
+    def build_attestation(
+        pcrs: Mapping[int, bytes],
+        module_id: str,
+        *,
+        user_data: Optional[bytes] = None,
+        public_key: Optional[bytes] = None,
+        nonce: Optional[bytes] = None,
+    ) -> AttestationDocument:
+        """Build an attestation document from an explicit PCR map.
+
+        No session is involved, so this suits stateless services that aggregate
+        measurements collected elsewhere. Every digest must be exactly
+        ``PCR_DIGEST_LEN`` bytes.
+        """
+
+        payload = _transport.build_attestation_payload(
+            pcrs,
+            module_id,
+            user_data=user_data,
+            public_key=public_key,
+            nonce=nonce,
+        )
+        return AttestationDocument.from_payload(payload)
+
+    def _check_reserved(self, slot: int, allow_reserved: bool) -> None:
+        if slot in self._reserved_slots and not allow_reserved:
+            raise NsmPcrReservedError(
+                f"PCR slot {slot} is reserved; pass allow_reserved=True to extend it"
+            )
+
+    def _check_extend_len(self, data: Sized, what: str = "data to extend") -> None:
+        if len(data) > self._max_extend_len:
+            raise NsmExtendTooLargeError(
+                f"{what} is {len(data)} bytes, over the {self._max_extend_len}-byte limit; "
+                "hash it first (or use extend_pcr_from_file) and extend with the digest"
+            )
+
+    def _session_blob(self) -> bytes:
+        transport = self._require_transport()
+        module_id = str(transport.describe_nsm()["module_id"]).encode("ascii")
+        bitmap = bytearray(_transport.PCR_SLOTS // 8)
+        for slot in transport.locked_slots():
+            bitmap[slot // 8] |= 1 << (slot % 8)
+        certificates = transport.describe_certificates()
+        parts = [
+            _SESSION_MAGIC,
+            bytes([_SESSION_VERSION, len(module_id)]),
+            module_id,
+            b"".join(transport.describe_pcr_bank()),
+            bytes(bitmap),
+            bytes([len(certificates)]),
+        ]
+        labels = {
+            slot: (transport.certificate_label(slot) or "").encode("utf-8")
+            for slot in certificates
+        }
+        for slot, label in labels.items():
+            if len(label) > 0xFFFF:
+                raise NsmError(
+                    f"certificate label for slot {slot} is {len(label)} bytes; "
+                    "session blobs allow at most 65535"
+                )
+        for slot, certificate in certificates.items():
+            parts.append(bytes([slot]) + len(certificate).to_bytes(4, "big") + certificate)
+            parts.append(len(labels[slot]).to_bytes(2, "big") + labels[slot])
+        blob = b"".join(parts)
+        if len(blob) > _SESSION_MAX_LEN:
+            raise NsmError(
+                f"session blob of {len(blob)} bytes exceeds the {_SESSION_MAX_LEN}-byte limit"
+            )
+        return blob
+
+    def _check_attestation_inputs(
+        self, public_key: Optional[bytes], nonce: Optional[bytes]
+    ) -> None:
+        for name, value, (low, high) in (
+            ("nonce", nonce, self._nonce_len_range),
+            ("public key", public_key, self._public_key_len_range),
+        ):
+            if value is not None and not low <= len(value) <= high:
+                raise NsmAttestationInputError(
+                    f"{name} is {len(value)} bytes; expected between {low} and {high}"
+                )
+
+    def _check_certificate_len(self, slot: int, certificate: bytes) -> None:
+        if len(certificate) < self._min_certificate_len:
+            raise NsmCertificateTooShortError(
+                f"certificate for slot {slot} is {len(certificate)} bytes, "
+                f"shorter than the {self._min_certificate_len}-byte minimum"
+            )
+
+    @staticmethod
+    def _normalise_pcr_selection(pcr_selection: Iterable[int]) -> List[int]:
+        selection = sorted(set(pcr_selection))
+        if not selection:
+            raise NsmError("PCR selection must not be empty")
+        for slot in selection:
+            if not 0 <= slot < _transport.PCR_SLOTS:
+                raise NsmInvalidPcrError(f"PCR slot {slot} is out of range")
+        return selection
+
+    def _sealing_key(self, selection: List[int]) -> Tuple[bytes, bytes]:
+        transport = self._require_transport()
+        context = b"nsm-seal-v1" + bytes(selection)
+        material = context + b"".join(transport.describe_pcr(slot) for slot in selection)
+        return transport.derive_key(material, 32), context
+
+    def _attestation_signing_key(self) -> Any:
+        try:
+            from cryptography.hazmat.primitives.asymmetric import ec, ed25519
+        except ImportError as exc:  # pragma: no cover - optional dependency
+            raise NsmError(
+                "attestation signing requires the 'cryptography' package; "
+                "install the 'verifier' extra",
+                cause=exc,
+            )
+
+        transport = self._require_transport()
+        context = b"nsm-attestation-signing-v1:" + self._signing_algorithm.encode("ascii")
+        if self._signing_algorithm == "eddsa":
+            return ed25519.Ed25519PrivateKey.from_private_bytes(transport.derive_key(context, 32))
+        # Reduce 64 derived bytes into [1, n - 1] so the scalar is a valid P-384 key.
+        order = int("ff" * 24 + "c7634d81f4372ddf581a0db248b0a77aecec196accc52973", 16)
+        scalar = int.from_bytes(transport.derive_key(context, 64), "big") % (order - 1) + 1
+        return ec.derive_private_key(scalar, ec.SECP384R1())
+
+    def _attestation_options(self) -> Dict[str, Any]:
+        return {
+            "bind_module_id": self._bind_module_id,
+            "bind_locks": self._bind_locks,
+            "leaf_cert_slot": self._leaf_cert_slot,
+            "canonical_digest": self._canonical_digest,
+        }
 
     def _require_transport(self) -> _transport.NsmTransport:
         if self._transport is None:
@@ -160,3 +1849,321 @@ class NsmClient:
         """Public alias for the native extension version."""
 
         return _transport.sdk_version()
+
+
+class AttestationDigestState:
+    """Handle for an attestation digest computed with ``attestation_digest_*``."""
+
+    __slots__ = ("_hasher",)
+
+    def __init__(self, hasher: Any) -> None:
+        self._hasher: Optional[Any] = hasher
+
+    def _require_active(self) -> Any:
+        if self._hasher is None:
+            raise NsmAttestationError("attestation digest has already been finalized")
+        return self._hasher
+
+
+class NsmReadOnlyView:
+    """Read-only facade over an :class:`NsmClient`.
+
+    Only describe, random and attestation calls are forwarded; mutating methods
+    do not exist on the view. Calls go to the live client, so changes made
+    through it are visible immediately. The client is held in a closure that
+    only dispatches the forwarded names, not in an attribute, so the view
+    cannot be used to reach mutating methods by accident. Python offers no hard
+    isolation, though: code that inspects closures can still find the client,
+    so hand out views to limit mistakes, not to contain untrusted code.
+    """
+
+    __slots__ = ("_call",)
+
+    _FORWARDED = frozenset(
+        {
+            "describe_pcr",
+            "describe_pcr_raw",
+            "describe_certificate",
+            "describe_nsm",
+            "get_random",
+            "get_attestation",
+            "get_attestation_raw",
+        }
+    )
+
+    def __init__(self, client: NsmClient) -> None:
+        def call(name: str, *args: Any, **kwargs: Any) -> Any:
+            if name not in NsmReadOnlyView._FORWARDED:
+                raise AttributeError(f"read-only view does not expose '{name}'")
+            return getattr(client, name)(*args, **kwargs)
+
+        object.__setattr__(self, "_call", call)
+
+    def __getattr__(self, name: str) -> Any:
+        raise AttributeError(f"read-only view does not expose '{name}'")
+
+    def __setattr__(self, name: str, value: Any) -> None:
+        raise AttributeError("read-only view attributes cannot be set")
+
+    def describe_pcr(self, slot: int) -> PcrValue:
+        return cast(PcrValue, self._call("describe_pcr", slot))
+
+    def describe_pcr_raw(self, slot: int) -> dict:
+        return cast(dict, self._call("describe_pcr_raw", slot))
+
+    def describe_certificate(self, slot: int, format: str = "der") -> bytes:
+        return cast(bytes, self._call("describe_certificate", slot, format))
+
+    def describe_nsm(self) -> dict:
+        return cast(dict, self._call("describe_nsm"))
+
+    def get_random(self, length: int) -> bytes:
+        return cast(bytes, self._call("get_random", length))
+
+    def get_attestation(self, **options: Any) -> AttestationDocument:
+        return cast(AttestationDocument, self._call("get_attestation", **options))
+
+    def get_attestation_raw(self, **options: Any) -> dict:
+        return cast(dict, self._call("get_attestation_raw", **options))
+
+
+def _check_public_key_format(public_key: Optional[bytes], public_key_format: str) -> None:
+    if public_key_format not in ("raw", "spki_der", "pem"):
+        raise NsmError(
+            f"unsupported public key format '{public_key_format}' (use raw, spki_der, pem)"
+        )
+    if public_key is None:
+        return
+    if public_key_format == "pem" and not (
+        public_key.lstrip().startswith(b"-----BEGIN PUBLIC KEY-----")
+        and b"-----END PUBLIC KEY-----" in public_key
+    ):
+        raise NsmAttestationInputError("public key is not a PEM 'PUBLIC KEY' block")
+    if public_key_format == "spki_der" and public_key[:1] != b"\x30":
+        raise NsmAttestationInputError("public key is not a DER SubjectPublicKeyInfo")
+
+
+def _read_exact(fd: int, length: int) -> bytes:
+    chunks = []
+    while length:
+        chunk = os.read(fd, length)
+        if not chunk:
+            raise NsmError("unexpected end of stream while reading session blob")
+        chunks.append(chunk)
+        length -= len(chunk)
+    return b"".join(chunks)
+
+
+def _parse_session_blob(
+    blob: bytes,
+) -> Tuple[str, bytes, bytes, List[Tuple[int, bytes, Optional[str]]]]:
+    offset = 0
+
+    def take(length: int, what: str) -> bytes:
+        nonlocal offset
+        if offset + length > len(blob):
+            raise NsmError(f"session blob is truncated in the {what}")
+        chunk = blob[offset : offset + length]
+        offset += length
+        return chunk
+
+    if take(4, "header") != _SESSION_MAGIC:
+        raise NsmError("session blob has an unknown format")
+    version = take(1, "header")[0]
+    if version != _SESSION_VERSION:
+        raise NsmError(
+            f"session blob version {version} is not supported (expected {_SESSION_VERSION})"
+        )
+    try:
+        module_id = take(take(1, "module ID")[0], "module ID").decode("ascii")
+        bank = take(_transport.PCR_SLOTS * _transport.PCR_DIGEST_LEN, "PCR bank")
+        bitmap = take(_transport.PCR_SLOTS // 8, "lock bitmap")
+        certificates: List[Tuple[int, bytes, Optional[str]]] = []
+        for index in range(take(1, "certificate count")[0]):
+            what = f"certificate {index}"
+            slot = take(1, what)[0]
+            certificate = take(int.from_bytes(take(4, what), "big"), what)
+            label = take(int.from_bytes(take(2, what), "big"), what).decode("utf-8")
+            certificates.append((slot, certificate, label or None))
+    except UnicodeDecodeError as exc:
+        raise NsmError("session blob contains invalid text", cause=exc)
+    if offset != len(blob):
+        raise NsmError(f"session blob has {len(blob) - offset} trailing bytes")
+    return module_id, bank, bitmap, certificates
+
+
+def _merkle_levels(digests: Sequence[bytes]) -> List[List[bytes]]:
+    """Return every level of the PCR Merkle tree, leaves first and root last."""
+
+    level = [hashlib.new(_transport.DIGEST_ALGORITHM, b"\x00" + d).digest() for d in digests]
+    levels = [level]
+    while len(level) > 1:
+        level = [
+            hashlib.new(_transport.DIGEST_ALGORITHM, b"\x01" + level[i] + level[i + 1]).digest()
+            for i in range(0, len(level), 2)
+        ]
+        levels.append(level)
+    return levels
+
+
+def _file_digest(path: str, chunk_size: int = 64 * 1024) -> bytes:
+    hasher = hashlib.new(_transport.DIGEST_ALGORITHM)
+    try:
+        with open(path, "rb") as handle:
+            for chunk in iter(lambda: handle.read(chunk_size), b""):
+                hasher.update(chunk)
+    except OSError as exc:
+        raise NsmError(f"unable to read measurement file '{path}'", cause=exc)
+    return hasher.digest()
+
+
+def _b64url(data: bytes) -> str:
+    return base64.urlsafe_b64encode(data).rstrip(b"=").decode("ascii")
+
+
+def _jwt_value(value: Any) -> Any:
+    if isinstance(value, (bytes, bytearray, memoryview)):
+        return _b64url(bytes(value))
+    if isinstance(value, Mapping):
+        return {str(key): _jwt_value(item) for key, item in value.items()}
+    if isinstance(value, (list, tuple, set, frozenset)):
+        return [_jwt_value(item) for item in value]
+    return value
+
+
+def _aesgcm() -> Any:
+    try:
+        from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+    except ImportError as exc:  # pragma: no cover - optional dependency
+        raise NsmError(
+            "sealing requires the 'cryptography' package; install the 'verifier' extra",
+            cause=exc,
+        )
+    return AESGCM
+
+
+def _load_der_certificate(data: bytes, origin: str) -> Any:
+    try:
+        from cryptography import x509
+    except ImportError as exc:  # pragma: no cover - optional dependency
+        raise NsmError(
+            "certificate parsing requires the 'cryptography' package; "
+            "install the 'verifier' extra",
+            cause=exc,
+        )
+    try:
+        return x509.load_der_x509_certificate(bytes(data))
+    except ValueError as exc:
+        raise NsmCertificateError(f"certificate in {origin} is not valid DER", cause=exc)
+
+
+def _public_state_digest(client: NsmClient, *, include_certs: bool) -> bytes:
+    transport = client._require_transport()
+    hasher = hashlib.sha256()
+    for digest in transport.describe_pcr_bank():
+        hasher.update(digest)
+    flags = set(transport.locked_slots())
+    hasher.update(bytes(slot in flags for slot in range(_transport.PCR_SLOTS)))
+    if include_certs:
+        certificates = transport.describe_certificates()
+        for slot in range(_transport.CERTIFICATE_SLOTS):
+            certificate = certificates.get(slot)
+            if certificate is None:
+                hasher.update(b"\x00")
+                continue
+            hasher.update(b"\x01" + len(certificate).to_bytes(8, "big") + certificate)
+    return hasher.digest()
+
+
+def sessions_state_equal(a: NsmClient, b: NsmClient, *, include_certs: bool = False) -> bool:
+    """Return whether two open clients expose identical public state.
+
+    PCR banks and lock maps are always compared, certificate contents only with
+    ``include_certs``. Module IDs and device paths are ignored. The comparison
+    runs in constant time over digests of both states.
+    """
+
+    return hmac.compare_digest(
+        _public_state_digest(a, include_certs=include_certs),
+        _public_state_digest(b, include_certs=include_certs),
+    )
+
+
+def verify_attestation(
+    doc: Mapping[str, Any],
+    expected_pcrs: Mapping[int, bytes],
+    expected_nonce: Optional[bytes] = None,
+    max_age_secs: Optional[int] = None,
+    *,
+    bind_module_id: bool = False,
+    bind_locks: bool = False,
+    canonical_digest: bool = False,
+) -> Dict[str, bool]:
+    """Check a raw attestation payload and report each check separately.
+
+    ``pcrs_ok``: every slot in ``expected_pcrs`` is present with that digest.
+    ``nonce_ok``: the nonce equals ``expected_nonce`` (always true when omitted).
+    ``fresh_ok``: the timestamp is at most ``max_age_secs`` old and not in the
+    future (always true when omitted). ``digest_ok``: the digest matches one
+    recomputed from the payload with the given binding options. ``ok`` is the
+    conjunction. Digest, PCR and nonce comparisons are constant time.
+    """
+
+    pcrs = {int(slot): bytes(value) for slot, value in (doc.get("pcrs") or {}).items()}
+    pcrs_ok = True
+    for slot, digest in expected_pcrs.items():
+        actual = pcrs.get(slot, b"")
+        pcrs_ok &= hmac.compare_digest(actual, bytes(digest))
+
+    nonce = doc.get("nonce")
+    if expected_nonce is None:
+        nonce_ok = True
+    else:
+        nonce_ok = nonce is not None and hmac.compare_digest(bytes(nonce), expected_nonce)
+
+    if max_age_secs is None:
+        fresh_ok = True
+    else:
+        age = int(time.time()) - int(doc["timestamp"])
+        fresh_ok = 0 <= age <= max_age_secs
+
+    pcrs_digest = doc.get("pcrs_digest")
+    try:
+        if pcrs_digest is None:
+            recomputed = _transport.build_attestation_payload(
+                pcrs,
+                str(doc["module_id"]),
+                locked_pcrs=doc.get("locked_pcrs") or (),
+                user_data=doc.get("user_data"),
+                public_key=doc.get("public_key"),
+                nonce=nonce,
+                channel_binding=doc.get("channel_binding"),
+                bind_module_id=bind_module_id,
+                bind_locks=bind_locks,
+                canonical_digest=canonical_digest,
+            )["digest"]
+        else:
+            # PCRs were withheld; the attested bank digest stands in for them.
+            recomputed = _transport.NsmTransport._attestation_digest(
+                {},
+                doc.get("user_data"),
+                doc.get("public_key"),
+                nonce,
+                module_id=str(doc["module_id"]) if bind_module_id else None,
+                locked_pcrs=(doc.get("locked_pcrs") or ()) if bind_locks else None,
+                canonical=canonical_digest,
+                channel_binding=doc.get("channel_binding"),
+                pcrs_digest=bytes(pcrs_digest),
+            )
+        digest_ok = hmac.compare_digest(cast(bytes, recomputed), bytes(doc["digest"]))
+    except NsmAttestationError:
+        digest_ok = False
+
+    report = {
+        "pcrs_ok": pcrs_ok,
+        "nonce_ok": nonce_ok,
+        "fresh_ok": fresh_ok,
+        "digest_ok": digest_ok,
+    }
+    report["ok"] = all(report.values())
+    return report
@@ -2,7 +2,8 @@
 
 from __future__ import annotations
 
-from typing import Optional
+import re
+from typing import Dict, Optional, Type
 
 
 class NsmError(Exception):
@@ -21,6 +22,10 @@ class NsmSessionClosedError(NsmError):
     """Raised when an operation is attempted on a closed session."""
 
 
+class NsmSessionFrozenError(NsmError):
+    """Raised when a mutating operation is attempted on a frozen session."""
+
+
 class NsmRandomError(NsmError):
     """Raised when random bytes cannot be produced by the NSM."""
 
@@ -33,9 +38,55 @@ class NsmCertificateError(NsmError):
     """Raised when certificate management operations fail."""
 
 
+class NsmCertificateTooShortError(NsmCertificateError):
+    """Raised when certificate data is shorter than the configured minimum."""
+
+
 class NsmAttestationError(NsmError):
     """Raised when attestation documents cannot be created or parsed."""
 
 
+class NsmAttestationInputError(NsmAttestationError):
+    """Raised when an attestation input falls outside its allowed length range."""
+
+
 class NsmPcrLockedError(NsmError):
     """Raised when attempting to modify a locked PCR slot."""
+
+
+class NsmExtendTooLargeError(NsmError):
+    """Raised when data to extend exceeds the session's ``max_extend_len``."""
+
+
+class NsmPcrReservedError(NsmError):
+    """Raised when application code extends a reserved PCR slot."""
+
+
+class NsmModuleIdError(NsmError):
+    """Raised when a supplied module ID does not match the expected format."""
+
+
+def error_code(error_type: Type[NsmError]) -> str:
+    """Return the stable code for ``error_type``, e.g. ``"PCR_LOCKED"``."""
+
+    name = error_type.__name__
+    name = name[len("Nsm") :] if name.startswith("Nsm") else name
+    name = name[: -len("Error")] if name.endswith("Error") else name
+    return re.sub(r"(?<!^)(?=[A-Z])", "_", name).upper() or "GENERIC"
+
+
+def error_codes() -> Dict[str, str]:
+    """Map the code of every :class:`NsmError` type to its description.
+
+    Built by walking the exception hierarchy, so new error types appear
+    automatically.
+    """
+
+    codes: Dict[str, str] = {}
+    pending = [NsmError]
+    while pending:
+        error_type = pending.pop()
+        doc = (error_type.__doc__ or "").strip().splitlines()
+        codes[error_code(error_type)] = doc[0] if doc else ""
+        pending.extend(error_type.__subclasses__())
+    return dict(sorted(codes.items()))
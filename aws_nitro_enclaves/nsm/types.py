@@ -2,8 +2,35 @@
 
 from __future__ import annotations
 
+import json
 from dataclasses import dataclass, field
-from typing import Any, Dict, FrozenSet, Mapping, MutableMapping, Optional
+from enum import Enum
+from typing import Any, Callable, Dict, FrozenSet, Mapping, MutableMapping, Optional, Tuple
+
+from ._transport import PCR_DIGEST_ALGORITHM
+from .errors import NsmError
+
+
+def hex_encoder(hex_case: str) -> Callable[[bytes], str]:
+    """Return a bytes-to-hex function for ``hex_case`` (``"lower"`` or ``"upper"``)."""
+
+    if hex_case == "lower":
+        return bytes.hex
+    if hex_case == "upper":
+        return lambda value: value.hex().upper()
+    raise NsmError(f"unsupported hex case '{hex_case}' (use lower, upper)")
+
+
+class Capability(str, Enum):
+    """Optional features a client may support, for feature detection."""
+
+    CBOR_DESCRIBE = "cbor_describe"
+    KEY_DERIVATION = "key_derivation"
+    SEALING = "sealing"
+    CERTIFICATE_CHAIN_VERIFICATION = "certificate_chain_verification"
+    MODULE_ID_BINDING = "module_id_binding"
+    LOCK_BINDING = "lock_binding"
+    CANONICAL_DIGEST = "canonical_digest"
 
 
 @dataclass(frozen=True)
@@ -28,11 +55,16 @@ class AttestationDocument:
     digest: bytes
     pcrs: Mapping[int, PcrValue]
     certificate: Optional[bytes] = None
-    cabundle: Optional[bytes] = None
+    cabundle: Optional[Tuple[bytes, ...]] = None
     user_data: Optional[bytes] = None
     public_key: Optional[bytes] = None
     nonce: Optional[bytes] = None
     locked_pcrs: FrozenSet[int] = field(default_factory=frozenset)
+    digest_algorithm: str = "sha256"
+    channel_binding: Optional[bytes] = None
+    fields_present: Tuple[str, ...] = ()
+    public_key_format: Optional[str] = None
+    pcrs_digest: Optional[bytes] = None
 
     @classmethod
     def from_payload(cls, payload: Mapping[str, Any]) -> "AttestationDocument":
@@ -45,7 +77,7 @@ class AttestationDocument:
         }
 
         pcr_map: Dict[int, PcrValue] = {}
-        for slot, value in payload.get("pcrs", {}).items():
+        for slot, value in (payload.get("pcrs") or {}).items():
             slot_int = int(slot)
             digest_bytes = bytes(value)
             pcr_map[slot_int] = PcrValue(
@@ -60,17 +92,24 @@ class AttestationDocument:
                 return None
             return bytes(value)
 
+        cabundle = payload.get("cabundle")
+
         return cls(
             module_id=module_id,
             timestamp=timestamp,
             digest=digest,
             pcrs=pcr_map,
             certificate=_optional_bytes("certificate"),
-            cabundle=_optional_bytes("cabundle"),
+            cabundle=tuple(bytes(cert) for cert in cabundle) if cabundle else None,
             user_data=_optional_bytes("user_data"),
             public_key=_optional_bytes("public_key"),
             nonce=_optional_bytes("nonce"),
             locked_pcrs=frozenset(locked_slots),
+            digest_algorithm=str(payload.get("digest_algorithm", "sha256")),
+            channel_binding=_optional_bytes("channel_binding"),
+            fields_present=tuple(str(name) for name in payload.get("fields_present", ())),
+            public_key_format=payload.get("public_key_format"),
+            pcrs_digest=_optional_bytes("pcrs_digest"),
         )
 
     def to_dict(self) -> MutableMapping[str, object]:
@@ -82,9 +121,49 @@ class AttestationDocument:
             "digest": self.digest.hex(),
             "pcrs": {slot: value.digest.hex() for slot, value in self.pcrs.items()},
             "certificate": self.certificate.decode("latin1") if self.certificate else None,
-            "cabundle": self.cabundle.decode("latin1") if self.cabundle else None,
+            "cabundle": (
+                [cert.decode("latin1") for cert in self.cabundle] if self.cabundle else None
+            ),
             "user_data": self.user_data.decode("latin1") if self.user_data else None,
             "public_key": self.public_key.decode("latin1") if self.public_key else None,
             "nonce": self.nonce.decode("latin1") if self.nonce else None,
             "locked_pcrs": sorted(self.locked_pcrs),
+            "digest_algorithm": self.digest_algorithm,
+            "channel_binding": self.channel_binding.hex() if self.channel_binding else None,
+            "fields_present": list(self.fields_present),
+            "public_key_format": self.public_key_format,
+            "pcrs_digest": self.pcrs_digest.hex() if self.pcrs_digest else None,
         }
+
+    def to_aws_json(self, hex_case: str = "lower") -> str:
+        """Return the document in the shape of the AWS attestation document spec.
+
+        Field names and order mirror the ``AttestationDoc`` struct of AWS's
+        ``aws-nitro-enclaves-nsm-api`` crate, which follows the "Attestation
+        document specification" section of the AWS Nitro Enclaves User Guide.
+        ``digest`` names the algorithm the PCR values were computed with: the
+        simulator's own hash, reported as ``"SIMULATED"`` rather than a SHA-2 name
+        so verifiers do not recompute PCRs with the wrong function.
+        ``timestamp`` is in milliseconds, ``pcrs`` maps slot indices to digests
+        and ``cabundle`` is a list. Binary values are hex strings, lowercase
+        unless ``hex_case="upper"``; absent optional fields are ``null``.
+        """
+
+        hex_bytes = hex_encoder(hex_case)
+
+        def _hex(value: Optional[bytes]) -> Optional[str]:
+            return hex_bytes(value) if value is not None else None
+
+        return json.dumps(
+            {
+                "module_id": self.module_id,
+                "digest": PCR_DIGEST_ALGORITHM.upper(),
+                "timestamp": self.timestamp * 1000,
+                "pcrs": {str(slot): hex_bytes(value.digest) for slot, value in self.pcrs.items()},
+                "certificate": _hex(self.certificate),
+                "cabundle": [hex_bytes(cert) for cert in self.cabundle or ()],
+                "public_key": _hex(self.public_key),
+                "user_data": _hex(self.user_data),
+                "nonce": _hex(self.nonce),
+            }
+        )
@@ -9,12 +9,40 @@ from aws_nitro_enclaves.nsm.errors import (
     NsmCertificateError,
     NsmDeviceNotFoundError,
     NsmError,
+    NsmExtendTooLargeError,
     NsmInvalidPcrError,
     NsmPcrLockedError,
     NsmRandomError,
 )
 
 
+def _make_certificate(subject: str, issuer: object = None) -> tuple:
+    """Return ``(der, key, name)`` for a test certificate signed by ``issuer``."""
+
+    import datetime
+
+    from cryptography import x509
+    from cryptography.hazmat.primitives import hashes, serialization
+    from cryptography.hazmat.primitives.asymmetric import ec
+    from cryptography.x509.oid import NameOID
+
+    key = ec.generate_private_key(ec.SECP256R1())
+    name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, subject)])
+    issuer_key, issuer_name = (key, name) if issuer is None else issuer[1:]
+    now = datetime.datetime(2024, 1, 1)
+    cert = (
+        x509.CertificateBuilder()
+        .subject_name(name)
+        .issuer_name(issuer_name)
+        .public_key(key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(now)
+        .not_valid_after(now + datetime.timedelta(days=1))
+        .sign(issuer_key, hashes.SHA256())
+    )
+    return cert.public_bytes(serialization.Encoding.DER), key, name
+
+
 @pytest.fixture()
 def fake_device(tmp_path: Path) -> str:
     sock = tmp_path / "nsm.sock"
@@ -73,7 +101,7 @@ def test_describe_and_extend_pcr(fake_device: str) -> None:
         assert original.digest == bytes([0] * 32)
         assert original.locked is False
 
-        updated = client.extend_pcr(0, b"hello")
+        updated = client.extend_pcr(0, b"hello", allow_reserved=True)
         assert updated.slot == 0
         assert updated.digest != original.digest
         assert updated.locked is False
@@ -123,7 +151,7 @@ def test_pcr_locking_prevents_extensions(fake_device: str) -> None:
         info = client.describe_pcr(0)
         assert info.locked is True
         with pytest.raises(NsmPcrLockedError):
-            client.extend_pcr(0, b"later")
+            client.extend_pcr(0, b"later", allow_reserved=True)
 
 
 def test_lock_range_affects_prefix(fake_device: str) -> None:
@@ -151,3 +179,1452 @@ def test_get_attestation_raw_matches_document(fake_device: str) -> None:
         doc = client.get_attestation(user_data=b"payload")
         assert doc.module_id == raw["module_id"]
         assert set(doc.locked_pcrs) == set(raw["locked_pcrs"])
+
+
+def test_extend_pcr_with_tag_prefixes_data(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        tagged = client.extend_pcr(0, b"payload", tag=b"boot:")
+        prefixed = client.extend_pcr(1, b"boot:payload")
+        untagged = client.extend_pcr(2, b"payload")
+        framed = client.extend_pcr(3, b"\x00\x05boot:payload")
+        assert tagged.digest != prefixed.digest
+        assert tagged.digest != untagged.digest
+        assert tagged.digest == framed.digest
+        assert client.extend_pcr(4, b":payload", tag=b"boot").digest != tagged.digest
+        with pytest.raises(NsmError):
+            client.extend_pcr(5, b"payload", tag=b"")
+        with pytest.raises(NsmError, match="65535"):
+            client.extend_pcr(5, b"payload", tag=b"t" * 0x10000)
+
+
+def test_version_at_least_ignores_prerelease(monkeypatch: pytest.MonkeyPatch) -> None:
+    from aws_nitro_enclaves.nsm import _transport, version_at_least
+
+    monkeypatch.setattr(_transport, "sdk_version", lambda: "1.2.0rc1")
+    assert version_at_least(1, 2, 0)
+    assert version_at_least(1, 1, 9)
+    assert not version_at_least(1, 2, 1)
+    assert not version_at_least(2, 0, 0)
+
+
+def test_attestation_reports_digest_algorithm(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        doc = client.get_attestation()
+        assert doc.digest_algorithm == "sha256"
+        assert len(doc.digest) == 32
+        assert doc.to_dict()["digest_algorithm"] == "sha256"
+        assert client.describe_nsm()["digest_algorithm"] == "sha256"
+
+
+def test_is_canonical_nsm_path_rejects_redirection(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    from aws_nitro_enclaves.nsm import _transport, is_canonical_nsm_path
+
+    device = tmp_path / "nsm"
+    device.touch()
+    monkeypatch.setattr(_transport, "DEFAULT_DEVICE_PATH", str(device))
+    alias = tmp_path / "alias"
+    alias.symlink_to(device)
+    decoy = tmp_path / "decoy"
+    decoy.touch()
+    redirected = tmp_path / "redirected"
+    redirected.symlink_to(decoy)
+
+    assert is_canonical_nsm_path(str(device))
+    assert is_canonical_nsm_path(str(alias))
+    assert not is_canonical_nsm_path(str(redirected))
+    assert not is_canonical_nsm_path(str(tmp_path / "missing"))
+
+
+def test_extend_pcr_from_file_uses_file_digest(fake_device: str, tmp_path: Path) -> None:
+    import hashlib
+
+    image = tmp_path / "image.bin"
+    image.write_bytes(b"kernel" * 1000)
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        streamed = client.extend_pcr_from_file(0, str(image), chunk_size=7)
+        direct = client.extend_pcr(1, hashlib.sha256(image.read_bytes()).digest())
+        assert streamed.digest == direct.digest
+
+        with pytest.raises(NsmError):
+            client.extend_pcr_from_file(2, str(tmp_path / "missing.bin"))
+        assert client.describe_pcr(2).digest == bytes(32)
+
+
+def test_find_duplicate_pcrs(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        assert client.find_duplicate_pcrs() == []
+        client.extend_pcr(3, b"same")
+        client.extend_pcr(9, b"same")
+        client.extend_pcr(4, b"other")
+        assert client.find_duplicate_pcrs() == [[3, 9]]
+        zero_groups = client.find_duplicate_pcrs(ignore_zero=False)
+        assert [3, 9] in zero_groups
+        assert any(0 in group and 31 in group for group in zero_groups)
+
+
+def test_bind_module_id_changes_digest(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device, bind_module_id=True) as client:
+        doc = client.get_attestation(nonce=b"n")
+        preimage = bytes(32 * 32) + doc.module_id.encode("ascii") + b"n"
+        assert doc.digest == hashlib.sha256(preimage).digest()
+    with NsmClient(device_path=fake_device) as client:
+        doc = client.get_attestation(nonce=b"n")
+        assert doc.digest == hashlib.sha256(bytes(32 * 32) + b"n").digest()
+
+
+def test_get_random_buffer_returns_bytearray(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        buffer = client.get_random_buffer(4096)
+        assert isinstance(buffer, bytearray)
+        assert len(buffer) == 4096
+        assert any(buffer)
+        buffer[0] ^= 0xFF
+        with pytest.raises(NsmRandomError):
+            client.get_random_buffer(0)
+
+
+def test_build_attestation_from_explicit_pcrs(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmAttestationError
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.extend_pcr(2, b"measured")
+        session_doc = client.get_attestation(user_data=b"u")
+        pcrs = {slot: value.digest for slot, value in session_doc.pcrs.items()}
+
+    doc = NsmClient.build_attestation(pcrs, session_doc.module_id, user_data=b"u")
+    assert doc.digest == session_doc.digest
+    assert doc.pcrs[2].digest == session_doc.pcrs[2].digest
+    assert doc.module_id == session_doc.module_id
+
+    with pytest.raises(NsmAttestationError):
+        NsmClient.build_attestation({0: b"short"}, "module")
+    with pytest.raises(NsmAttestationError):
+        NsmClient.build_attestation({99: bytes(32)}, "module")
+
+
+def test_iter_locked_pcrs(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        assert list(client.iter_locked_pcrs()) == []
+        client.lock_pcr(5)
+        client.lock_pcrs(2)
+        locked = client.iter_locked_pcrs()
+        assert next(locked) == 0
+        assert list(locked) == [1, 5]
+
+
+def test_derive_key_is_deterministic_per_context(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        first = client.derive_key(b"request-1", 48)
+        assert len(first) == 48
+        assert client.derive_key(b"request-1", 48) == first
+        assert client.derive_key(b"request-1", 16) == first[:16]
+        assert client.derive_key(b"request-2", 48) != first
+        with pytest.raises(NsmError):
+            client.derive_key(b"ctx", 0)
+
+
+def test_derive_key_differs_between_sessions(fake_device: str, monkeypatch) -> None:
+    from aws_nitro_enclaves.nsm import _transport
+
+    # Sessions started in the same second get identical shim randomness.
+    monkeypatch.setattr(_transport.NsmTransport, "get_random", lambda self, n: bytes(n))
+    with NsmClient(device_path=fake_device) as first:
+        with NsmClient(device_path=fake_device) as second:
+            assert first.derive_key(b"label", 32) != second.derive_key(b"label", 32)
+
+
+def test_set_certificate_rejects_empty_payload(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        with pytest.raises(NsmCertificateError, match="slot 2"):
+            client.set_certificate(2, b"")
+        with pytest.raises(NsmCertificateError):
+            client.describe_certificate(2)
+
+
+def test_can_attest_reflects_prerequisites(fake_device: str) -> None:
+    client = NsmClient(device_path=fake_device)
+    assert not client.can_attest()
+    with client:
+        assert client.can_attest()
+        assert not client.can_attest(require_leaf_cert=True)
+        client.set_certificate(0, b"leaf")
+        assert client.can_attest(require_leaf_cert=True)
+    assert not client.can_attest()
+
+
+def test_get_random_matrix_shape(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        rows = client.get_random_matrix(4, 16)
+        assert len(rows) == 4
+        assert all(isinstance(row, bytes) and len(row) == 16 for row in rows)
+        with pytest.raises(NsmRandomError):
+            client.get_random_matrix(0, 16)
+        with pytest.raises(NsmRandomError):
+            client.get_random_matrix(4, 0)
+
+
+def test_bind_locks_commits_lock_bitmap(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device, bind_locks=True) as client:
+        unlocked = client.get_attestation()
+        client.lock_pcr(0)
+        client.lock_pcr(9)
+        locked = client.get_attestation()
+        assert unlocked.digest != locked.digest
+        bitmap = bytes([0b00000001, 0b00000010, 0, 0])
+        assert locked.digest == hashlib.sha256(bytes(32 * 32) + bitmap).digest()
+
+
+def test_cbor_encoding_is_canonical() -> None:
+    from aws_nitro_enclaves.nsm import _cbor
+
+    assert _cbor.dumps(0) == b"\x00"
+    assert _cbor.dumps(-1) == b"\x20"
+    assert _cbor.dumps(1000) == b"\x19\x03\xe8"
+    assert _cbor.dumps(b"\x01\x02") == b"\x42\x01\x02"
+    assert _cbor.dumps([1, None, True]) == b"\x83\x01\xf6\xf5"
+    assert _cbor.dumps({"bb": 1, "a": 2}) == _cbor.dumps({"a": 2, "bb": 1})
+    assert _cbor.dumps({"bb": 1, "a": 2}) == b"\xa2\x61a\x02\x62bb\x01"
+
+
+def test_describe_nsm_cbor_matches_describe_nsm(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import _cbor
+
+    with NsmClient(device_path=fake_device) as client:
+        encoded = client.describe_nsm_cbor()
+        assert encoded == _cbor.dumps(client.describe_nsm())
+        assert client.describe_nsm()["module_id"].encode("ascii") in encoded
+
+
+def test_is_pristine_tracks_pcrs_locks_and_certificates(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        assert client.is_pristine()
+        client.lock_pcr(4)
+        assert not client.is_pristine()
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.extend_pcr(1, b"x")
+        assert not client.is_pristine()
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.set_certificate(3, b"cert")
+        assert not client.is_pristine()
+        client.remove_certificate(3)
+        assert client.is_pristine()
+
+
+def test_leaf_cert_slot_selects_certificate_and_cabundle(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, leaf_cert_slot=2) as client:
+        client.set_certificate(0, b"root")
+        client.set_certificate(1, b"intermediate")
+        client.set_certificate(2, b"leaf")
+        doc = client.get_attestation()
+        assert doc.certificate == b"leaf"
+        assert doc.cabundle == (b"root", b"intermediate")
+        assert client.can_attest(require_leaf_cert=True)
+
+    with NsmClient(device_path=fake_device) as client:
+        doc = client.get_attestation()
+        assert doc.certificate is None
+        assert doc.cabundle is None
+
+    with pytest.raises(NsmCertificateError):
+        NsmClient(device_path=fake_device, leaf_cert_slot=4)
+
+
+def test_module_id_injection_is_validated(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmModuleIdError
+
+    module_id = "0123456789abcdef0123456789abcdef"
+    with NsmClient(device_path=fake_device, module_id=module_id) as client:
+        assert client.describe_nsm()["module_id"] == module_id
+        assert client.get_attestation().module_id == module_id
+
+    for bad in ["", "g" * 32, "ABCDEF", "é", "a" * 65]:
+        with pytest.raises(NsmModuleIdError):
+            NsmClient(device_path=fake_device, module_id=bad)
+
+
+def test_seconds_since_last_change(fake_device: str, monkeypatch: pytest.MonkeyPatch) -> None:
+    from aws_nitro_enclaves.nsm import _transport
+
+    clock = [1000.0]
+    monkeypatch.setattr(_transport.time, "monotonic", lambda: clock[0])
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        clock[0] = 1030.0
+        assert client.seconds_since_last_change() == 30
+        client.extend_pcr(0, b"x")
+        clock[0] = 1035.5
+        assert client.seconds_since_last_change() == 5
+        client.describe_pcr(0)
+        assert client.seconds_since_last_change() == 5
+
+
+def test_get_attestation_with_fixed_timestamp(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        doc = client.get_attestation(timestamp=1_700_000_000)
+        assert doc.timestamp == 1_700_000_000
+        assert client.get_attestation_raw(timestamp=0)["timestamp"] == 0
+        assert client.get_attestation().timestamp > 1_700_000_000
+        with pytest.raises(NsmError):
+            client.get_attestation(timestamp=-1)
+
+
+def test_extend_pcr_many_validates_every_chunk(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        batched = client.extend_pcr_many(0, [b"a", bytearray(b"b")])
+        client.extend_pcr(1, b"a")
+        assert batched.digest == client.extend_pcr(1, b"b").digest
+
+        with pytest.raises(NsmError, match="chunk 1 must be bytes-like, got list"):
+            client.extend_pcr_many(2, [b"ok", [1, 2], b"never"])
+        assert client.describe_pcr(2).digest == bytes(32)
+
+        client.lock_pcr(3)
+        with pytest.raises(NsmPcrLockedError):
+            client.extend_pcr_many(3, [b"x"])
+
+
+def test_get_attestation_aws_json_shape(fake_device: str) -> None:
+    import json
+
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"\x30\x01")
+        encoded = client.get_attestation_aws_json(nonce=b"\xab", timestamp=12)
+    document = json.loads(encoded)
+    assert document["digest"] == "SIMULATED"
+    assert document["timestamp"] == 12_000
+    assert document["pcrs"]["0"] == "00" * 32
+    assert len(document["pcrs"]) == 32
+    assert document["certificate"] == "3001"
+    assert document["cabundle"] == []
+    assert document["nonce"] == "ab"
+    assert document["user_data"] is None
+
+
+def test_get_attestation_without_certificates(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"leaf")
+        client.set_certificate(1, b"root")
+        full = client.get_attestation()
+        assert full.certificate == b"leaf"
+        assert full.cabundle == (b"root",)
+        minimal = client.get_attestation(include_certificates=False)
+        assert minimal.certificate is None
+        assert minimal.cabundle is None
+
+
+def test_freeze_blocks_mutation_but_allows_reads(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import NsmSessionFrozenError
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.set_certificate(0, b"cert")
+        assert not client.is_frozen
+        client.freeze()
+        client.freeze()
+        assert client.is_frozen
+        for mutate in (
+            lambda: client.extend_pcr(0, b"x"),
+            lambda: client.lock_pcr(0),
+            lambda: client.lock_pcrs(2),
+            lambda: client.set_certificate(1, b"cert"),
+            lambda: client.remove_certificate(0),
+        ):
+            with pytest.raises(NsmSessionFrozenError):
+                mutate()
+        assert client.describe_pcr(0).digest == bytes(32)
+        assert client.get_attestation().certificate == b"cert"
+
+
+def test_rng_backend_is_reported(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        backend = client.rng_backend()
+        assert backend in ("os", "seeded")
+        assert client.describe_nsm()["rng_backend"] == backend
+
+
+def test_certificate_fingerprints_cover_occupied_slots(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device) as client:
+        assert client.certificate_fingerprints() == {}
+        client.set_certificate(0, b"leaf")
+        client.set_certificate(3, b"root")
+        assert client.certificate_fingerprints() == {
+            0: hashlib.sha256(b"leaf").digest(),
+            3: hashlib.sha256(b"root").digest(),
+        }
+
+
+def test_pcr_tsv_round_trip(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmPcrReservedError
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as source:
+        source.extend_pcr(0, b"boot")
+        source.extend_pcr(7, b"app")
+        exported = source.export_pcrs_tsv()
+        expected = [source.describe_pcr(slot).digest for slot in range(32)]
+    lines = exported.splitlines()
+    assert len(lines) == 32
+    assert lines[7] == f"7\t{expected[7].hex()}"
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as target:
+        target.import_pcrs_tsv(exported + "\n")
+        assert [target.describe_pcr(slot).digest for slot in range(32)] == expected
+
+        with pytest.raises(NsmError, match="line 2"):
+            target.import_pcrs_tsv(f"1\t{'11' * 32}\n2\tzz")
+        assert target.describe_pcr(1).digest == expected[1]
+        with pytest.raises(NsmError, match="line 1"):
+            target.import_pcrs_tsv("3\tabcd")
+        target.lock_pcr(4)
+        with pytest.raises(NsmPcrLockedError):
+            target.import_pcrs_tsv(f"4\t{'22' * 32}")
+
+    with NsmClient(device_path=fake_device) as guarded:
+        with pytest.raises(NsmPcrReservedError, match="slot 0"):
+            guarded.import_pcrs_tsv(exported)
+        assert not any(guarded.pcr_bank_bytes())
+        guarded.import_pcrs_tsv(exported, allow_reserved=True)
+        assert guarded.describe_pcr(7).digest == expected[7]
+
+
+def test_attestation_count_tracks_every_variant(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        assert client.attestation_count() == 0
+        client.get_attestation()
+        client.get_attestation_raw()
+        client.get_attestation_aws_json()
+        assert client.attestation_count() == 3
+        assert client.describe_nsm()["attestation_count"] == 3
+
+
+def test_sessions_state_equal(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import sessions_state_equal
+
+    a = NsmClient(device_path=fake_device, reserved_slots=())
+    b = NsmClient(device_path=fake_device, reserved_slots=())
+    with a, b:
+        assert sessions_state_equal(a, b, include_certs=True)
+        a.extend_pcr(1, b"x")
+        assert not sessions_state_equal(a, b)
+        b.extend_pcr(1, b"x")
+        assert sessions_state_equal(a, b)
+        a.lock_pcr(2)
+        assert not sessions_state_equal(a, b)
+        b.lock_pcr(2)
+        a.set_certificate(0, b"cert")
+        assert sessions_state_equal(a, b)
+        assert not sessions_state_equal(a, b, include_certs=True)
+        b.set_certificate(0, b"cert")
+        assert sessions_state_equal(a, b, include_certs=True)
+
+
+def test_seal_nonce_ignores_shim_rng(fake_device: str, monkeypatch) -> None:
+    pytest.importorskip("cryptography")
+    from aws_nitro_enclaves.nsm import _transport
+
+    monkeypatch.setattr(_transport.NsmTransport, "get_random", lambda self, n: bytes(n))
+    with NsmClient(device_path=fake_device) as client:
+        first = client.seal(b"secret", [9])
+        second = client.seal(b"secret", [9])
+        assert first[:12] != second[:12]
+        assert client.unseal(second, [9]) == b"secret"
+
+
+def test_seal_unseal_bound_to_pcr_state(fake_device: str) -> None:
+    pytest.importorskip("cryptography")
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.extend_pcr(0, b"boot")
+        sealed = client.seal(b"secret", [0, 1])
+        assert b"secret" not in sealed
+        assert client.unseal(sealed, [1, 0]) == b"secret"
+
+        with pytest.raises(NsmError):
+            client.unseal(sealed, [0])
+        client.extend_pcr(3, b"unrelated")
+        assert client.unseal(sealed, [0, 1]) == b"secret"
+        client.extend_pcr(1, b"changed")
+        with pytest.raises(NsmError):
+            client.unseal(sealed, [0, 1])
+
+
+def test_verify_certificate_chain(fake_device: str) -> None:
+    pytest.importorskip("cryptography")
+
+    root = _make_certificate("root")
+    intermediate = _make_certificate("intermediate", root)
+    leaf = _make_certificate("leaf", intermediate)
+    stranger = _make_certificate("stranger")
+
+    with NsmClient(device_path=fake_device) as client:
+        assert not client.verify_certificate_chain()
+        client.set_certificate(0, leaf[0])
+        client.set_certificate(1, intermediate[0])
+        assert not client.verify_certificate_chain()
+        assert client.verify_certificate_chain(trusted_root=root[0])
+        assert not client.verify_certificate_chain(trusted_root=stranger[0])
+
+        client.set_certificate(2, root[0])
+        assert client.verify_certificate_chain()
+        client.set_certificate(1, stranger[0])
+        assert not client.verify_certificate_chain()
+
+        client.set_certificate(2, b"not a certificate")
+        with pytest.raises(NsmCertificateError):
+            client.verify_certificate_chain()
+
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, leaf[0])
+        assert not client.verify_certificate_chain()
+        client.set_certificate(0, root[0])
+        assert client.verify_certificate_chain()
+
+    with NsmClient(device_path=fake_device, leaf_cert_slot=2) as client:
+        client.set_certificate(0, intermediate[0])
+        client.set_certificate(2, leaf[0])
+        assert client.verify_certificate_chain(trusted_root=root[0])
+        client.remove_certificate(2)
+        assert not client.verify_certificate_chain()
+
+
+def test_canonical_digest_prefixes_slot_and_length(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device, canonical_digest=True) as client:
+        doc = client.get_attestation(user_data=b"u")
+    preimage = b"".join(
+        slot.to_bytes(4, "big") + (32).to_bytes(4, "big") + bytes(32) for slot in range(32)
+    )
+    assert doc.digest == hashlib.sha256(preimage + b"u").digest()
+
+
+def test_get_random_bits_masks_excess(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        for bits in (1, 7, 8, 13, 256):
+            assert 0 <= client.get_random_bits(bits) < 2**bits
+        with pytest.raises(NsmRandomError):
+            client.get_random_bits(0)
+
+
+def test_supported_capabilities_follow_construction_flags(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.types import Capability
+
+    plain = NsmClient(device_path=fake_device).supported_capabilities()
+    assert Capability.CBOR_DESCRIBE in plain
+    assert Capability.LOCK_BINDING not in plain
+    bound = NsmClient(device_path=fake_device, bind_locks=True).supported_capabilities()
+    assert Capability.LOCK_BINDING in bound
+    assert all(isinstance(capability, Capability) for capability in bound)
+
+
+def test_get_attestation_with_external_digest(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmAttestationError
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as client:
+        client.extend_pcr(0, b"x")
+        doc = client.get_attestation_with_digest(b"\x11" * 32, nonce=b"n")
+        assert doc.digest == b"\x11" * 32
+        assert doc.pcrs[0].digest == client.describe_pcr(0).digest
+        assert doc.nonce == b"n"
+        with pytest.raises(NsmAttestationError):
+            client.get_attestation_with_digest(b"\x11" * 16)
+        with pytest.raises(NsmError, match="unsupported public key format"):
+            client.get_attestation_with_digest(bytes(32), public_key_format="der")
+        with pytest.raises(NsmError, match="not a PEM"):
+            client.get_attestation_with_digest(
+                bytes(32), public_key=b"\x01" * 32, public_key_format="pem"
+            )
+        client.set_certificate(0, b"leaf")
+        client.set_certificate(1, b"mid")
+        client.set_certificate(2, b"root")
+        doc = client.get_attestation_with_digest(bytes(32), cabundle_order="root_first")
+        assert doc.cabundle == (b"root", b"mid")
+
+
+def test_describe_nsm_cache_refreshes_volatile_fields(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        first = client.describe_nsm()
+        client.lock_pcr(3)
+        client.set_certificate(0, b"cert")
+        second = client.describe_nsm()
+        assert list(first) == list(second)
+        assert second["module_id"] == first["module_id"]
+        assert second["locked_pcrs"] == [3]
+        assert second["certificates"] == 1
+        second["locked_pcrs"].append(9)
+        assert client.describe_nsm()["locked_pcrs"] == [3]
+
+        client._require_transport().set_module_id("abcd")
+        assert client.describe_nsm()["module_id"] == "abcd"
+
+
+def _pem(der: bytes) -> str:
+    import base64
+    import textwrap
+
+    body = "\n".join(textwrap.wrap(base64.b64encode(der).decode("ascii"), 64))
+    return f"-----BEGIN CERTIFICATE-----\n{body}\n-----END CERTIFICATE-----\n"
+
+
+def test_load_certificate_chain_from_pem_bundle(fake_device: str, tmp_path: Path) -> None:
+    bundle = tmp_path / "chain.pem"
+    bundle.write_text(_pem(b"leaf-der") + "junk\n" + _pem(b"intermediate-der"))
+    with NsmClient(device_path=fake_device, leaf_cert_slot=1) as client:
+        assert client.load_certificate_chain(str(bundle)) == [1, 2]
+        assert client.describe_certificate(1) == b"leaf-der"
+        assert client.describe_certificate(2) == b"intermediate-der"
+
+    too_long = tmp_path / "long.pem"
+    too_long.write_text("".join(_pem(bytes([index]) * 8) for index in range(5)))
+    with NsmClient(device_path=fake_device) as client:
+        with pytest.raises(NsmCertificateError, match="5 certificates"):
+            client.load_certificate_chain(str(too_long))
+        assert client.certificate_fingerprints() == {}
+
+
+def test_generate_self_signed_cert(fake_device: str) -> None:
+    pytest.importorskip("cryptography")
+    from cryptography import x509
+    from cryptography.hazmat.primitives import serialization
+    from cryptography.hazmat.primitives.asymmetric import ec, ed25519
+
+    for key in (ec.generate_private_key(ec.SECP384R1()), ed25519.Ed25519PrivateKey.generate()):
+        private_der = key.private_bytes(
+            serialization.Encoding.DER,
+            serialization.PrivateFormat.PKCS8,
+            serialization.NoEncryption(),
+        )
+        public_der = key.public_key().public_bytes(
+            serialization.Encoding.DER, serialization.PublicFormat.SubjectPublicKeyInfo
+        )
+        der = NsmClient.generate_self_signed_cert(public_der, private_der, "enclave-test")
+        cert = x509.load_der_x509_certificate(der)
+        assert "enclave-test" in cert.subject.rfc4514_string()
+        with NsmClient(device_path=fake_device) as client:
+            client.set_certificate(0, der)
+            assert client.verify_certificate_chain(trusted_root=der)
+
+    other = ec.generate_private_key(ec.SECP256R1()).public_key().public_bytes(
+        serialization.Encoding.DER, serialization.PublicFormat.SubjectPublicKeyInfo
+    )
+    with pytest.raises(NsmCertificateError):
+        NsmClient.generate_self_signed_cert(other, private_der, "mismatch")
+
+
+def test_reserved_slots_require_explicit_opt_in(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmPcrReservedError
+
+    with NsmClient(device_path=fake_device) as client:
+        with pytest.raises(NsmPcrReservedError):
+            client.extend_pcr(0, b"app")
+        with pytest.raises(NsmPcrReservedError):
+            client.extend_pcr_many(7, [b"app"])
+        assert client.describe_pcr(0).digest == bytes(32)
+        client.extend_pcr(0, b"firmware", allow_reserved=True)
+        client.extend_pcr(8, b"app")
+
+    with NsmClient(device_path=fake_device, reserved_slots=[16]) as client:
+        client.extend_pcr(0, b"app")
+        with pytest.raises(NsmPcrReservedError):
+            client.extend_pcr(16, b"app")
+
+
+def test_extend_pcr_object_accepts_buffers(fake_device: str) -> None:
+    import array
+
+    with NsmClient(device_path=fake_device) as client:
+        expected = client.extend_pcr(8, b"\x01\x02").digest
+        assert client.extend_pcr_object(9, bytearray(b"\x01\x02")).digest == expected
+        assert client.extend_pcr_object(10, memoryview(b"\x01\x02")).digest == expected
+        assert client.extend_pcr_object(11, array.array("B", [1, 2])).digest == expected
+        with pytest.raises(TypeError, match="got str"):
+            client.extend_pcr_object(12, "text")
+
+
+def test_get_attestation_msgpack_encodes_bytes_as_bin(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import _msgpack
+
+    assert _msgpack.dumps({"a": b"\x01", "n": -1, "big": 300}) == (
+        b"\x83\xa1a\xc4\x01\x01\xa1n\xff\xa3big\xcd\x01\x2c"
+    )
+    with NsmClient(device_path=fake_device) as client:
+        encoded = client.get_attestation_msgpack(nonce=b"nonce-1", timestamp=7)
+        raw = client.get_attestation_raw(nonce=b"nonce-1", timestamp=7)
+    assert encoded[0] == 0x80 | len(raw)
+    assert b"\xa5nonce\xc4\x07nonce-1" in encoded
+    assert b"\xa9timestamp\x07" in encoded
+
+
+def test_is_real_enclave_false_on_host(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import is_real_enclave
+
+    with NsmClient(device_path=fake_device):
+        assert is_real_enclave() is False
+
+
+def test_extend_pcrs_is_all_or_nothing(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.lock_pcr(12)
+        with pytest.raises(NsmPcrLockedError):
+            client.extend_pcrs([10, 11, 12], b"event")
+        assert client.describe_pcr(10).digest == bytes(32)
+        assert client.describe_pcr(11).digest == bytes(32)
+
+        digests = client.extend_pcrs([10, 11], b"event")
+        assert set(digests) == {10, 11}
+        assert digests[10] == digests[11] == client.describe_pcr(10).digest
+        with pytest.raises(NsmError, match="duplicates"):
+            client.extend_pcrs([13, 13], b"event")
+
+
+def test_certificate_labels_follow_slot_contents(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"leaf", label="tenant-a")
+        client.set_certificate(1, b"intermediate")
+        assert client.certificate_label(0) == "tenant-a"
+        assert client.list_certificate_slots() == {0: "tenant-a", 1: None}
+
+        client.remove_certificate(0)
+        assert client.certificate_label(0) is None
+        client.set_certificate(1, b"replacement", label="tenant-b")
+        assert client.list_certificate_slots() == {1: "tenant-b"}
+
+
+def test_derive_module_id_is_stable_and_injectable(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import derive_module_id
+
+    module_id = derive_module_id(b"node-1")
+    assert module_id == derive_module_id(b"node-1")
+    assert module_id != derive_module_id(b"node-2")
+    assert len(module_id) == 32
+    with NsmClient(device_path=fake_device, module_id=module_id) as client:
+        assert client.describe_nsm()["module_id"] == module_id
+    with NsmClient(device_path=fake_device) as client:
+        assert len(client.describe_nsm()["module_id"]) == len(module_id)
+
+
+def test_lock_pcrs_strict_rejects_oversized_range(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        with pytest.raises(NsmInvalidPcrError, match="exceeds"):
+            client.lock_pcrs(33, strict=True)
+        assert list(client.iter_locked_pcrs()) == []
+        assert client.lock_pcrs(32, strict=True)
+        assert len(list(client.iter_locked_pcrs())) == 32
+
+
+def test_certificate_fingerprints_with_algorithm(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"leaf")
+        assert client.certificate_fingerprints_with("sha256") == client.certificate_fingerprints()
+        assert client.certificate_fingerprints_with("sha512") == {
+            0: hashlib.sha512(b"leaf").digest()
+        }
+        with pytest.raises(NsmError, match="unsupported fingerprint algorithm 'md5'"):
+            client.certificate_fingerprints_with("md5")
+
+
+def test_attestation_size_tracks_inputs(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import _cbor
+
+    with NsmClient(device_path=fake_device) as client:
+        baseline = client.attestation_size()
+        assert client.attestation_size(user_data=b"x" * 100) > baseline + 100
+        payload = client.get_attestation_raw(nonce=b"n" * 8)
+        assert client.attestation_size(nonce=b"n" * 8) == len(_cbor.dumps(payload))
+        assert client.attestation_count() == 1
+        assert list(client._recent_digests) == [payload["digest"]]
+
+
+def test_min_certificate_len_rejects_truncated_certificates(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmCertificateTooShortError
+
+    with NsmClient(device_path=fake_device, min_certificate_len=100) as client:
+        with pytest.raises(NsmCertificateTooShortError, match="slot 0 is 4 bytes"):
+            client.set_certificate(0, b"leaf")
+        assert client.list_certificate_slots() == {}
+        client.set_certificate(0, b"\x30" * 100)
+        assert client.describe_certificate(0) == b"\x30" * 100
+
+
+def test_error_codes_cover_the_hierarchy() -> None:
+    from aws_nitro_enclaves.nsm import error_codes
+
+    codes = error_codes()
+    assert codes["PCR_LOCKED"] == "Raised when attempting to modify a locked PCR slot."
+    assert codes["GENERIC"] == "Base exception for all NSM-related failures."
+    assert "CERTIFICATE_TOO_SHORT" in codes
+    assert "DEVICE_NOT_FOUND" in codes
+
+
+def test_sample_attestation_is_a_stable_test_vector() -> None:
+    from aws_nitro_enclaves.nsm import sample_attestation
+
+    payload = sample_attestation()
+    assert payload == sample_attestation()
+    assert payload["digest"].hex() == (
+        "700fadd340db0b0f366cb0f1e61004f7f7a0743e5eb2121d161e385aaaa860be"
+    )
+    assert payload["pcrs"][5] == b"\x05" * 32
+    assert payload["locked_pcrs"] == list(range(8))
+
+    x509 = pytest.importorskip("cryptography.x509")
+    from cryptography.hazmat.primitives import serialization
+
+    certificate = x509.load_der_x509_certificate(payload["certificate"])
+    certificate.verify_directly_issued_by(certificate)
+    raw = serialization.Encoding.Raw, serialization.PublicFormat.Raw
+    assert certificate.public_key().public_bytes(*raw) == payload["public_key"]
+
+
+def test_describe_certificate_as_pem_round_trips(fake_device: str, tmp_path: Path) -> None:
+    der = bytes(range(200))
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, der)
+        pem = client.describe_certificate(0, format="pem")
+        assert pem.decode("ascii") == _pem(der)
+        bundle = tmp_path / "chain.pem"
+        bundle.write_bytes(pem)
+        client.remove_certificate(0)
+        assert client.load_certificate_chain(str(bundle)) == [0]
+        assert client.describe_certificate(0) == der
+        with pytest.raises(NsmError, match="unsupported certificate format"):
+            client.describe_certificate(0, format="txt")
+
+
+def test_recent_digest_collision_detects_stuck_state(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, digest_history=3) as client:
+        client.get_attestation(user_data=b"same")
+        client.get_attestation(user_data=b"same")
+        assert not client.recent_digest_collision()
+        client.get_attestation(user_data=b"same")
+        assert client.recent_digest_collision()
+        client.get_attestation(user_data=b"different")
+        assert not client.recent_digest_collision()
+    with pytest.raises(NsmError, match="at least 2"):
+        NsmClient(digest_history=1)
+
+
+def test_to_fixture_code_replays_session_state(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import sessions_state_equal
+
+    with NsmClient(device_path=fake_device, reserved_slots=()) as source:
+        source.extend_pcr(3, b"boot")
+        source.extend_pcr(9, b"app")
+        source.set_certificate(1, b"cert", label="tenant's")
+        source.lock_pcr(9)
+        code = source.to_fixture_code()
+        assert code == source.to_fixture_code()
+
+        namespace: dict = {}
+        exec(compile(code, "<fixture>", "exec"), namespace)
+        with NsmClient(device_path=fake_device, module_id=namespace["MODULE_ID"]) as replay:
+            namespace["apply_fixture"](replay)
+            assert sessions_state_equal(source, replay, include_certs=True)
+            namespace["apply_fixture"](replay)
+            assert sessions_state_equal(source, replay, include_certs=True)
+            assert replay.certificate_label(1) == "tenant's"
+            assert replay.describe_nsm()["module_id"] == source.describe_nsm()["module_id"]
+
+
+def test_self_attest_roundtrip(fake_device: str) -> None:
+    with NsmClient(
+        device_path=fake_device, bind_module_id=True, bind_locks=True, canonical_digest=True
+    ) as client:
+        client.extend_pcr(9, b"app")
+        client.lock_pcr(9)
+        assert client.self_attest_roundtrip() is True
+        assert client.attestation_count() == 1
+
+
+def test_attestation_signature_rejects_tampering(fake_device: str) -> None:
+    pytest.importorskip("cryptography")
+    import dataclasses
+
+    for algorithm in ("es384", "eddsa"):
+        with NsmClient(device_path=fake_device, signing_algorithm=algorithm) as client:
+            assert client.self_attest_roundtrip()
+            doc = client.get_attestation(nonce=b"nonce")
+            signature, public_key = client.sign_attestation(doc)
+            assert NsmClient.verify_attestation_signature(doc, signature, public_key)
+            tampered = dataclasses.replace(doc, nonce=b"other")
+            assert not NsmClient.verify_attestation_signature(tampered, signature, public_key)
+            with NsmClient(device_path=fake_device, signing_algorithm=algorithm) as other:
+                _, other_key = other.sign_attestation(doc)
+            assert not NsmClient.verify_attestation_signature(doc, signature, other_key)
+            assert not NsmClient.verify_attestation_signature(doc, signature, b"junk")
+
+
+def test_get_random_chunked_streams_total(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        chunks = list(client.get_random_chunked(10_000, 4096))
+        assert [len(chunk) for chunk in chunks] == [4096, 4096, 1808]
+        with pytest.raises(NsmRandomError, match="chunk"):
+            client.get_random_chunked(10, 0)
+
+
+def test_production_client_warns_when_simulated(fake_device: str) -> None:
+    import logging
+
+    records: list = []
+    handler = logging.Handler()
+    handler.emit = records.append  # type: ignore[method-assign]
+    logger = logging.getLogger("aws_nitro_enclaves.nsm.client")
+    logger.addHandler(handler)
+    try:
+        with NsmClient(device_path=fake_device) as client:
+            assert client.is_simulated()
+        assert records == []
+        with NsmClient(device_path=fake_device, production=True):
+            pass
+    finally:
+        logger.removeHandler(handler)
+    assert [record.levelno for record in records] == [logging.WARNING]
+    assert "simulated" in records[0].getMessage()
+
+
+def test_user_data_commitment_round_trip(fake_device: str) -> None:
+    payload = b"large document" * 1000
+    with NsmClient(device_path=fake_device) as client:
+        commitment = client.commit_user_data(payload)
+        doc = client.get_attestation(user_data=commitment)
+    assert len(doc.user_data or b"") == 32
+    assert NsmClient.verify_user_data_commitment(doc, payload)
+    assert not NsmClient.verify_user_data_commitment(doc, payload + b"!")
+
+
+def test_get_attestation_cabundle_order(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        for slot, name in enumerate([b"leaf", b"intermediate", b"issuer", b"root"]):
+            client.set_certificate(slot, name)
+        leaf_first = client.get_attestation()
+        root_first = client.get_attestation(cabundle_order="root_first")
+        with pytest.raises(NsmError, match="unsupported cabundle order"):
+            client.get_attestation(cabundle_order="random")
+    assert leaf_first.cabundle == (b"intermediate", b"issuer", b"root")
+    assert root_first.cabundle == (b"root", b"issuer", b"intermediate")
+    assert root_first.certificate == leaf_first.certificate == b"leaf"
+
+
+def test_pcr_matches_file(fake_device: str, tmp_path: Path) -> None:
+    image = tmp_path / "image.eif"
+    image.write_bytes(b"kernel" * 1000)
+    other = tmp_path / "other.eif"
+    other.write_bytes(b"initrd")
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr_from_file(0, str(image), allow_reserved=True)
+        assert client.pcr_matches_file(0, str(image))
+        assert not client.pcr_matches_file(0, str(other))
+        assert not client.pcr_matches_file(1, str(image))
+        with pytest.raises(NsmError, match="unable to read"):
+            client.pcr_matches_file(0, str(tmp_path / "missing"))
+
+
+def test_signing_algorithm_is_validated_and_described(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.client import SIGNING_ALGORITHMS
+
+    with NsmClient(device_path=fake_device) as client:
+        assert client.describe_nsm()["signing_algorithm"] == "es384"
+    with NsmClient(device_path=fake_device, signing_algorithm="eddsa") as client:
+        assert client.describe_nsm()["signing_algorithm"] == "eddsa"
+    assert SIGNING_ALGORITHMS == {"es384": -35, "eddsa": -8}
+    with pytest.raises(NsmError, match="unsupported signing algorithm 'rs256'"):
+        NsmClient(signing_algorithm="rs256")
+
+
+def test_certificates_present(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(2, b"cert")
+        assert client.certificates_present([0, 2, 3]) == {0: False, 2: True, 3: False}
+        with pytest.raises(NsmCertificateError, match="slot 4 is out of range"):
+            client.certificates_present([0, 4])
+
+
+def test_export_and_import_locks(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as source:
+        source.lock_pcr(2)
+        source.lock_pcr(17)
+        locks = source.export_locks()
+    assert len(locks) == 32
+    assert [slot for slot, locked in enumerate(locks) if locked] == [2, 17]
+
+    with NsmClient(device_path=fake_device) as target:
+        target.import_locks(locks)
+        assert list(target.iter_locked_pcrs()) == [2, 17]
+        with pytest.raises(NsmPcrLockedError, match="cannot be unlocked"):
+            target.import_locks([False] * 32)
+        with pytest.raises(NsmInvalidPcrError, match="32 entries"):
+            target.import_locks([True])
+
+
+def test_get_attestation_channel_binding(fake_device: str) -> None:
+    import hashlib
+
+    binding = b"tls-exporter-value"
+    with NsmClient(device_path=fake_device) as client:
+        plain = client.get_attestation(nonce=b"n")
+        bound = client.get_attestation(nonce=b"n", channel_binding=binding)
+        bank = b"".join(client.describe_pcr(slot).digest for slot in range(32))
+    assert plain.channel_binding is None
+    assert bound.channel_binding == binding
+    assert plain.digest == hashlib.sha256(bank + b"n").digest()
+    expected = hashlib.sha256(bank + b"n" + len(binding).to_bytes(4, "big") + binding)
+    assert bound.digest == expected.digest()
+
+
+def test_attestation_reports_fields_present(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        assert client.get_attestation().fields_present == ()
+        client.set_certificate(0, b"leaf")
+        client.set_certificate(1, b"root")
+        raw = client.get_attestation_raw(user_data=b"u", nonce=b"n")
+    assert raw["fields_present"] == ["user_data", "nonce", "certificate", "cabundle"]
+    with NsmClient(device_path=fake_device) as client:
+        raw = client.get_attestation_raw(channel_binding=b"")
+        unbound = client.get_attestation_raw()
+    assert raw["fields_present"] == ["channel_binding"]
+    assert raw["digest"] != unbound["digest"]
+
+
+def test_extend_pcr_env_measures_canonical_form(
+    fake_device: str, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    import hashlib
+
+    monkeypatch.setenv("APP_MODE", "prod")
+    monkeypatch.setenv("APP_REGION", "eu-west-1")
+    monkeypatch.delenv("APP_UNSET", raising=False)
+    expected_input = hashlib.sha256(b"APP_MODE=prod\nAPP_REGION=eu-west-1\nAPP_UNSET=\n").digest()
+    with NsmClient(device_path=fake_device) as client:
+        reference = client.extend_pcr(8, expected_input).digest
+        measured = client.extend_pcr_env(9, ["APP_UNSET", "APP_REGION", "APP_MODE"])
+        assert measured.digest == reference
+        with pytest.raises(NsmError, match="'APP_UNSET' is not set"):
+            client.extend_pcr_env(10, ["APP_UNSET"], allow_missing=False)
+        assert client.describe_pcr(10).digest == bytes(32)
+
+        # "A=1\nB=2" would otherwise measure the same bytes as A="1", B="2".
+        monkeypatch.setenv("APP_A", "1\nAPP_B=2")
+        with pytest.raises(NsmError, match="'APP_A' contains a newline"):
+            client.extend_pcr_env(10, ["APP_A"])
+        with pytest.raises(NsmError, match="not measurable"):
+            client.extend_pcr_env(10, ["APP_A=1"])
+        assert client.describe_pcr(10).digest == bytes(32)
+
+
+def test_readonly_view_forwards_reads_only(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        view = client.readonly_view()
+        assert not hasattr(view, "extend_pcr")
+        assert not hasattr(view, "set_certificate")
+        with pytest.raises(AttributeError):
+            view.lock_pcr = lambda slot: True  # type: ignore[attr-defined]
+        assert not hasattr(view, "_client")
+        with pytest.raises(AttributeError, match="does not expose 'lock_pcrs'"):
+            view._call("lock_pcrs", 32)
+        with pytest.raises(AttributeError):
+            view._call = None  # type: ignore[misc]
+        assert not list(client.iter_locked_pcrs())
+
+        client.extend_pcr(9, b"event")
+        assert view.describe_pcr(9) == client.describe_pcr(9)
+        assert len(view.get_random(8)) == 8
+        assert view.get_attestation(nonce=b"n").nonce == b"n"
+        assert view.describe_nsm()["attestation_count"] == 1
+
+
+def test_verify_attestation_reports_each_check(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import verify_attestation
+
+    with NsmClient(device_path=fake_device, bind_locks=True) as client:
+        client.extend_pcr(9, b"app")
+        client.lock_pcr(9)
+        expected = {9: client.describe_pcr(9).digest}
+        payload = client.get_attestation_raw(nonce=b"n-1", user_data=b"u")
+
+    report = verify_attestation(payload, expected, b"n-1", 60, bind_locks=True)
+    assert report == {
+        "pcrs_ok": True,
+        "nonce_ok": True,
+        "fresh_ok": True,
+        "digest_ok": True,
+        "ok": True,
+    }
+    assert not verify_attestation(payload, expected)["digest_ok"]
+    stale = dict(payload, timestamp=payload["timestamp"] - 120)
+    report = verify_attestation(stale, {9: bytes(32)}, b"n-2", 60, bind_locks=True)
+    assert report["pcrs_ok"] is False
+    assert report["nonce_ok"] is False
+    assert report["fresh_ok"] is False
+    assert report["digest_ok"] is True
+    assert report["ok"] is False
+
+
+def test_send_and_recv_session_over_pipe(fake_device: str) -> None:
+    import os
+
+    from aws_nitro_enclaves.nsm import sessions_state_equal
+
+    read_fd, write_fd = os.pipe()
+    try:
+        with NsmClient(device_path=fake_device) as source:
+            source.extend_pcr(9, b"app")
+            source.lock_pcr(9)
+            source.set_certificate(0, b"leaf", label="tenant-a")
+            source.set_certificate(2, b"root")
+            source.send_to_fd(write_fd)
+            with NsmClient.recv_from_fd(read_fd, device_path=fake_device) as restored:
+                assert sessions_state_equal(source, restored, include_certs=True)
+                assert restored.list_certificate_slots() == {0: "tenant-a", 2: None}
+                assert restored.describe_nsm()["module_id"] == source.describe_nsm()["module_id"]
+
+            blob = bytearray(source._session_blob())
+            blob[4] = 99
+            os.write(write_fd, len(blob).to_bytes(4, "big") + bytes(blob))
+            with pytest.raises(NsmError, match="version 99 is not supported"):
+                NsmClient.recv_from_fd(read_fd, device_path=fake_device)
+    finally:
+        os.close(read_fd)
+        os.close(write_fd)
+
+
+def test_recv_from_fd_rejects_malformed_blobs(fake_device: str) -> None:
+    import os
+
+    with NsmClient(device_path=fake_device) as source:
+        source.set_certificate(0, b"leaf")
+        valid = source._session_blob()
+    cases = [
+        (b"NSMS\x01\x05", "truncated in the module ID"),
+        (valid[:-3], "truncated in the certificate 0"),
+        (valid + b"\x00", "1 trailing bytes"),
+    ]
+    read_fd, write_fd = os.pipe()
+    try:
+        for blob, message in cases:
+            os.write(write_fd, len(blob).to_bytes(4, "big") + blob)
+            with pytest.raises(NsmError, match=message):
+                NsmClient.recv_from_fd(read_fd, device_path=fake_device)
+        os.write(write_fd, (0xFFFFFFFF).to_bytes(4, "big"))
+        with pytest.raises(NsmError, match="exceeds"):
+            NsmClient.recv_from_fd(read_fd, device_path=fake_device)
+        with pytest.raises(NsmError, match="module ID from the blob"):
+            NsmClient.recv_from_fd(read_fd, device_path=fake_device, module_id="ab")
+        with NsmClient(device_path=fake_device) as source:
+            source.set_certificate(0, b"leaf", label="x" * 0x10000)
+            with pytest.raises(NsmError, match="at most 65535"):
+                source.send_to_fd(write_fd)
+    finally:
+        os.close(read_fd)
+        os.close(write_fd)
+
+
+def test_incremental_attestation_digest_matches_one_shot(fake_device: str) -> None:
+    chunks = [b"chunk-%d" % index * 100 for index in range(5)]
+    with NsmClient(device_path=fake_device, bind_module_id=True, bind_locks=True) as client:
+        client.extend_pcr(9, b"app")
+        client.lock_pcr(9)
+        state = client.attestation_digest_init()
+        for chunk in chunks:
+            client.attestation_digest_update(state, chunk)
+        digest = client.attestation_digest_finalize(state, nonce=b"n")
+        expected = client.get_attestation(user_data=b"".join(chunks), nonce=b"n").digest
+        assert digest == expected
+        assert client.get_attestation_with_digest(digest, nonce=b"n").digest == expected
+        with pytest.raises(NsmError, match="already been finalized"):
+            client.attestation_digest_update(state, b"late")
+
+
+def test_extended_slots_ignores_lock_only_slots(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        assert client.extended_slots() == []
+        client.extend_pcr(12, b"event")
+        client.extend_pcr(9, b"event")
+        client.lock_pcr(20)
+        assert client.extended_slots() == [9, 12]
+
+
+def test_pcr_bank_summary_identifies_state(fake_device: str) -> None:
+    import hashlib
+
+    with NsmClient(device_path=fake_device) as first, NsmClient(device_path=fake_device) as second:
+        assert first.pcr_bank_summary() == second.pcr_bank_summary()
+        first.extend_pcr(9, b"event")
+        assert first.pcr_bank_summary() != second.pcr_bank_summary()
+        second.extend_pcr(9, b"event")
+        assert first.pcr_bank_summary() == second.pcr_bank_summary()
+        zero = b"".join(
+            slot.to_bytes(4, "big") + (32).to_bytes(4, "big") + bytes(32) for slot in range(32)
+        )
+        with NsmClient(device_path=fake_device) as fresh:
+            assert fresh.pcr_bank_summary() == hashlib.sha256(zero).digest()
+
+
+def test_attestation_rejects_out_of_range_nonce(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmAttestationInputError
+
+    with NsmClient(device_path=fake_device, nonce_len_range=(8, 16)) as client:
+        assert client.get_attestation().nonce is None
+        assert client.get_attestation(nonce=b"n" * 8).nonce == b"n" * 8
+        with pytest.raises(NsmAttestationInputError, match="nonce is 3 bytes"):
+            client.get_attestation(nonce=b"abc")
+        with pytest.raises(NsmAttestationInputError, match="nonce is 17 bytes"):
+            client.get_attestation_with_digest(bytes(32), nonce=b"n" * 17)
+    with NsmClient(device_path=fake_device) as client:
+        with pytest.raises(NsmAttestationInputError, match="nonce is 0 bytes"):
+            client.get_attestation(nonce=b"")
+        with pytest.raises(NsmAttestationInputError, match="public key is 4097 bytes"):
+            client.get_attestation(public_key=b"k" * 4097)
+        assert client.attestation_count() == 0
+
+
+def test_replace_all_certificates_is_all_or_nothing(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"old-leaf", label="v1")
+        client.set_certificate(3, b"old-root")
+        with pytest.raises(NsmCertificateError, match="slot 1 must not be empty"):
+            client.replace_all_certificates({0: b"new-leaf", 1: b""})
+        assert client.list_certificate_slots() == {0: "v1", 3: None}
+
+        client.replace_all_certificates({0: b"new-leaf", 1: b"new-root"})
+        assert client.list_certificate_slots() == {0: None, 1: None}
+        assert client.describe_certificate(1) == b"new-root"
+
+
+def test_replace_all_certificates_rolls_back_and_excludes_readers(
+    fake_device: str, monkeypatch
+) -> None:
+    import threading
+
+    from aws_nitro_enclaves.nsm import _transport
+
+    original = _transport.NsmTransport.set_certificate
+
+    def flaky(self, slot, certificate, *, label=None):  # type: ignore[no-untyped-def]
+        if certificate == b"bad":
+            raise NsmCertificateError("simulated write failure")
+        original(self, slot, certificate, label=label)
+
+    monkeypatch.setattr(_transport.NsmTransport, "set_certificate", flaky)
+    with NsmClient(device_path=fake_device) as client:
+        client.set_certificate(0, b"old-leaf", label="v1")
+        client.set_certificate(3, b"old-root")
+        with pytest.raises(NsmCertificateError, match="simulated"):
+            client.replace_all_certificates({0: b"new-leaf", 1: b"bad"})
+        assert client.list_certificate_slots() == {0: "v1", 3: None}
+        assert client.describe_certificate(0) == b"old-leaf"
+
+        seen = []
+        reader = threading.Thread(target=lambda: seen.append(client.get_attestation()))
+        with client._require_transport()._certificate_lock:
+            reader.start()
+            reader.join(0.2)
+            assert not seen
+        reader.join()
+        assert seen[0].certificate == b"old-leaf"
+
+
+def test_get_attestation_records_public_key_format(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmAttestationInputError
+
+    pem = b"-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEA\n-----END PUBLIC KEY-----\n"
+    with NsmClient(device_path=fake_device) as client:
+        assert client.get_attestation().public_key_format is None
+        assert client.get_attestation(public_key=b"\x01" * 32).public_key_format == "raw"
+        doc = client.get_attestation(public_key=pem, public_key_format="pem")
+        assert doc.public_key_format == "pem"
+        assert doc.public_key == pem
+        with pytest.raises(NsmAttestationInputError, match="SubjectPublicKeyInfo"):
+            client.get_attestation(public_key=pem, public_key_format="spki_der")
+        with pytest.raises(NsmError, match="unsupported public key format"):
+            client.get_attestation(public_key=pem, public_key_format="jwk")
+
+
+def test_benchmark_random_reports_throughput(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        report = client.benchmark_random(64 * 1024, 4096)
+        assert report["bytes"] == 64 * 1024
+        assert report["elapsed_ms"] >= 0
+        assert report["mb_per_sec"] > 0
+        with pytest.raises(NsmRandomError):
+            client.benchmark_random(1024, 0)
+
+
+def test_evaluate_policy_accepts_any_listed_version(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr(8, b"image-v2")
+        current = client.describe_pcr(8).digest
+        report = client.evaluate_policy({8: [b"\x01" * 32, current], 9: bytes(32)})
+        assert report == {"allowed": True, "slots": {8: True, 9: True}}
+        report = client.evaluate_policy({8: b"\x01" * 32, 9: bytes(32)})
+        assert report == {"allowed": False, "slots": {8: False, 9: True}}
+        with pytest.raises(NsmInvalidPcrError):
+            client.evaluate_policy({32: bytes(32)})
+        with pytest.raises(NsmError, match="at least one"):
+            client.evaluate_policy({})
+        with pytest.raises(NsmError, match="no acceptable digests"):
+            client.evaluate_policy({8: []})
+        with pytest.raises(NsmError, match="must be 32 bytes, got 31"):
+            client.evaluate_policy({8: [current, bytes(31)]})
+
+
+def test_get_attestation_into_writes_cbor(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        buffer = bytearray(4096)
+        written = client.get_attestation_into(buffer, nonce=b"nonce")
+        assert written == client.attestation_size(nonce=b"nonce")
+        assert buffer[0] >> 5 == 5  # CBOR map
+        assert b"enonce\x45nonce" in buffer[:written]
+        assert not any(buffer[written:])
+        count, history = client.attestation_count(), list(client._recent_digests)
+        with pytest.raises(NsmError, match=f"needs {written} bytes but the buffer holds 8"):
+            client.get_attestation_into(bytearray(8), nonce=b"nonce")
+        assert client.attestation_count() == count
+        assert list(client._recent_digests) == history
+
+
+def test_detect_slot_swaps(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as reference:
+        kernel = reference.extend_pcr(8, b"kernel").digest
+        initrd = reference.extend_pcr(9, b"initrd").digest
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr(8, b"initrd")
+        client.extend_pcr(9, b"kernel")
+        expected = {8: kernel, 9: initrd, 10: bytes(32)}
+        assert client.detect_slot_swaps(expected) == [(8, 9)]
+        assert client.detect_slot_swaps({8: initrd, 9: kernel}) == []
+
+
+def test_hex_case_applies_to_json_and_tsv(fake_device: str) -> None:
+    import json
+
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr(9, b"event")
+        upper_tsv = client.export_pcrs_tsv(hex_case="upper")
+        assert upper_tsv == client.export_pcrs_tsv().upper()
+        client.import_pcrs_tsv(upper_tsv)
+        encoded = json.loads(
+            client.get_attestation_aws_json(nonce=b"\xab", timestamp=1, hex_case="upper")
+        )
+        assert encoded["nonce"] == "AB"
+        assert encoded["pcrs"]["9"] == client.describe_pcr(9).digest.hex().upper()
+        assert encoded["digest"] == "SIMULATED"
+        with pytest.raises(NsmError, match="unsupported hex case"):
+            client.export_pcrs_tsv(hex_case="mixed")
+
+
+def test_get_attestation_without_pcrs(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm import verify_attestation
+
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr(9, b"event")
+        full = client.get_attestation(nonce=b"n", timestamp=1)
+        minimal = client.get_attestation(nonce=b"n", timestamp=1, include_pcrs=False)
+        assert minimal.pcrs == {}
+        assert minimal.pcrs_digest is not None and full.pcrs_digest is None
+        assert minimal.digest != full.digest
+        raw = client.get_attestation_raw(nonce=b"n", include_pcrs=False)
+        assert raw["pcrs"] is None
+        assert verify_attestation(raw, {}, b"n")["digest_ok"]
+        raw["pcrs_digest"] = bytes(32)
+        assert not verify_attestation(raw, {}, b"n")["digest_ok"]
+
+
+def test_pcr_merkle_proof_verifies_single_slot(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device) as client:
+        digest = client.extend_pcr(9, b"event").digest
+        root = client.pcr_merkle_root()
+        proof = client.pcr_merkle_proof(9)
+        assert len(proof) == 5
+        assert NsmClient.verify_pcr_merkle_proof(root, 9, digest, proof)
+        assert not NsmClient.verify_pcr_merkle_proof(root, 10, digest, proof)
+        assert not NsmClient.verify_pcr_merkle_proof(root, 9, bytes(32), proof)
+        client.extend_pcr(10, b"other")
+        assert client.pcr_merkle_root() != root
+        with pytest.raises(NsmInvalidPcrError):
+            client.pcr_merkle_proof(32)
+
+
+def test_max_extend_len_rejects_oversized_input(fake_device: str) -> None:
+    with NsmClient(device_path=fake_device, max_extend_len=16) as client:
+        before = client.describe_pcr(9).digest
+        with pytest.raises(NsmExtendTooLargeError, match="hash it first"):
+            client.extend_pcr(9, bytes(17))
+        with pytest.raises(NsmExtendTooLargeError, match="chunk 1"):
+            client.extend_pcr_many(9, [b"ok", bytes(17)])
+        assert client.describe_pcr(9).digest == before
+        client.extend_pcr(9, bytes(16))
+    with pytest.raises(NsmError, match="greater than zero"):
+        NsmClient(max_extend_len=0)
+
+
+def test_pcr_bank_bytes_roundtrip(fake_device: str) -> None:
+    from aws_nitro_enclaves.nsm.errors import NsmPcrReservedError
+
+    with NsmClient(device_path=fake_device) as source:
+        source.extend_pcr(9, b"event")
+        source.lock_pcr(9)
+        bank = source.pcr_bank_bytes()
+    assert len(bank) == 32 * 32
+    with NsmClient(device_path=fake_device) as client:
+        client.set_pcr_bank_bytes(bank)
+        assert client.pcr_bank_bytes() == bank
+        client.lock_pcr(9)
+        client.set_pcr_bank_bytes(bank)
+        with pytest.raises(NsmPcrLockedError):
+            client.set_pcr_bank_bytes(bytes(len(bank)))
+        with pytest.raises(NsmError, match="must be 1024 bytes"):
+            client.set_pcr_bank_bytes(bank[:-1])
+        for slot in range(8):
+            reserved = bytearray(bank)
+            reserved[slot * 32] ^= 1
+            with pytest.raises(NsmPcrReservedError, match=f"slot {slot} is reserved"):
+                client.set_pcr_bank_bytes(bytes(reserved))
+        client.set_pcr_bank_bytes(bytes(reserved), allow_reserved=True)
+        assert client.pcr_bank_bytes() == bytes(reserved)
+
+
+def test_get_attestation_jwt_signs_with_session_algorithm(fake_device: str) -> None:
+    pytest.importorskip("cryptography")
+    import base64
+    import json
+
+    from cryptography.hazmat.primitives import hashes, serialization
+    from cryptography.hazmat.primitives.asymmetric import ec, ed25519, utils
+
+    def _decode(part: str) -> bytes:
+        return base64.urlsafe_b64decode(part + "=" * (-len(part) % 4))
+
+    der = serialization.Encoding.DER, serialization.PrivateFormat.PKCS8
+    ec_key = ec.generate_private_key(ec.SECP384R1())
+    ed_key = ed25519.Ed25519PrivateKey.generate()
+    ec_der = ec_key.private_bytes(*der, serialization.NoEncryption())
+    ed_der = ed_key.private_bytes(*der, serialization.NoEncryption())
+
+    with NsmClient(device_path=fake_device) as client:
+        client.extend_pcr(9, b"event")
+        token = client.get_attestation_jwt(ec_der, nonce=b"\xff\xfe", timestamp=1)
+        header, payload, signature = token.split(".")
+        assert json.loads(_decode(header)) == {"alg": "ES384", "typ": "JWT"}
+        claims = json.loads(_decode(payload))
+        assert _decode(claims["nonce"]) == b"\xff\xfe"
+        assert _decode(claims["pcrs"]["9"]) == client.describe_pcr(9).digest
+        raw = _decode(signature)
+        der_signature = utils.encode_dss_signature(
+            int.from_bytes(raw[:48], "big"), int.from_bytes(raw[48:], "big")
+        )
+        signing_input = f"{header}.{payload}".encode("ascii")
+        ec_key.public_key().verify(der_signature, signing_input, ec.ECDSA(hashes.SHA384()))
+        with pytest.raises(NsmError, match="P-384"):
+            client.get_attestation_jwt(ed_der)
+
+    with NsmClient(device_path=fake_device, signing_algorithm="eddsa") as client:
+        header, payload, signature = client.get_attestation_jwt(ed_der).split(".")
+        assert json.loads(_decode(header))["alg"] == "EdDSA"
+        ed_key.public_key().verify(_decode(signature), f"{header}.{payload}".encode("ascii"))